@@ -5,6 +5,7 @@
 package main
 
 import (
+	"flag"
 	"fmt"
 	"runtime/debug"
 	"strconv"
@@ -16,23 +17,59 @@ import (
 
 const SRPCPackage = "github.com/aperturerobotics/starpc/srpc"
 
+// generatorOptions are the plugin options.
+type generatorOptions struct {
+	// serviceIDs contains the service id overrides
+	serviceIDs serviceIDOverrides
+	// dispatch enables generating the unary Dispatch method on handlers
+	dispatch bool
+}
+
 func main() {
-	opts := protogen.Options{}
+	var flags flag.FlagSet
+	genOpts := &generatorOptions{serviceIDs: serviceIDOverrides{}}
+	flags.Var(genOpts.serviceIDs, "service_id", "override a service id: full.proto.Service=service.id")
+	flags.BoolVar(&genOpts.dispatch, "dispatch", false, "generate Dispatch for unary methods on handlers")
+	opts := protogen.Options{ParamFunc: flags.Set}
 	opts.Run(func(plugin *protogen.Plugin) error {
 		for _, f := range plugin.Files {
 			if !f.Generate || len(f.Services) == 0 {
 				continue
 			}
-			generatePluginFile(plugin, f)
+			generatePluginFile(plugin, f, genOpts)
 		}
 		plugin.SupportedFeatures = uint64(pluginpb.CodeGeneratorResponse_FEATURE_PROTO3_OPTIONAL)
 		return nil
 	})
 }
 
-func generatePluginFile(plugin *protogen.Plugin, file *protogen.File) {
+// serviceIDOverrides maps proto service full names to service IDs.
+//
+// Set with the service_id plugin option, for example:
+// --go-starpc_opt=service_id=my.pkg.v1.Service=gateway.Service
+type serviceIDOverrides map[string]string
+
+// String returns the overrides as a string.
+func (o serviceIDOverrides) String() string {
+	return fmt.Sprint(map[string]string(o))
+}
+
+// Set parses and adds an override in the form full.proto.Service=service.id.
+func (o serviceIDOverrides) Set(value string) error {
+	name, serviceID, ok := strings.Cut(value, "=")
+	if !ok || name == "" {
+		return fmt.Errorf("service_id: expected full.proto.Service=service.id: %q", value)
+	}
+	if strings.TrimSpace(serviceID) == "" {
+		return fmt.Errorf("service_id: empty service id for %s", name)
+	}
+	o[name] = serviceID
+	return nil
+}
+
+func generatePluginFile(plugin *protogen.Plugin, file *protogen.File, opts *generatorOptions) {
 	gf := plugin.NewGeneratedFile(file.GeneratedFilenamePrefix+"_srpc.pb.go", file.GoImportPath)
-	s := &srpc{gf, file}
+	s := &srpc{gf, file, opts}
 
 	s.P("// Code generated by protoc-gen-srpc. DO NOT EDIT.")
 	if bi, ok := debug.ReadBuildInfo(); ok {
@@ -51,6 +88,7 @@ func generatePluginFile(plugin *protogen.Plugin, file *protogen.File) {
 type srpc struct {
 	*protogen.GeneratedFile
 	file *protogen.File
+	opts *generatorOptions
 }
 
 func (s *srpc) Ident(path, ident string) string {
@@ -58,13 +96,18 @@ func (s *srpc) Ident(path, ident string) string {
 }
 
 // GetServiceID returns the service id for the srpc.
+// Defaults to the full name of the service unless overridden.
 func (s *srpc) GetServiceID(p *protogen.Service) (service string) {
-	return string(p.Desc.FullName())
+	fullName := string(p.Desc.FullName())
+	if serviceID, ok := s.opts.serviceIDs[fullName]; ok {
+		return serviceID
+	}
+	return fullName
 }
 
 // GetServiceAndMethodID returns the service and method for the srpc.
 func (s *srpc) GetServiceAndMethodID(p *protogen.Method) (service, method string) {
-	return string(p.Parent.Desc.FullName()), string(p.Desc.Name())
+	return s.GetServiceID(p.Parent), string(p.Desc.Name())
 }
 
 /*
@@ -73,6 +116,21 @@ func (s *srpc) ServiceMethodString(method *protogen.Method) string {
 }
 */
 
+// IsEmptyMessage checks if the message has no fields, like google.protobuf.Empty.
+func (s *srpc) IsEmptyMessage(msg *protogen.Message) bool {
+	return msg.Desc.Fields().Len() == 0
+}
+
+// HasSimpleClientMethod checks if a Simple client method variant is generated.
+//
+// Generated for unary methods with an empty input or output message.
+func (s *srpc) HasSimpleClientMethod(method *protogen.Method) bool {
+	if method.Desc.IsStreamingClient() || method.Desc.IsStreamingServer() {
+		return false
+	}
+	return s.IsEmptyMessage(method.Input) || s.IsEmptyMessage(method.Output)
+}
+
 func (s *srpc) InputType(method *protogen.Method) string {
 	return s.QualifiedGoIdent(method.Input.GoIdent)
 }
@@ -147,6 +205,9 @@ func (s *srpc) generateService(service *protogen.Service) {
 	s.P()
 	for _, method := range service.Methods {
 		s.P(s.generateClientSignature(method))
+		if s.HasSimpleClientMethod(method) {
+			s.P(s.generateClientSimpleSignature(method))
+		}
 	}
 	s.P("}")
 	s.P()
@@ -197,10 +258,14 @@ func (s *srpc) generateService(service *protogen.Service) {
 	s.P()
 	s.P("func (", s.ServerHandler(service), ") GetServiceID() string { return ", s.ServerServiceID(service), " }")
 	s.P()
+	// methodIDs is used for both GetMethodIDs and InvokeMethod so they match.
+	methodIDs := make([]string, len(service.Methods))
+	for i, method := range service.Methods {
+		_, methodIDs[i] = s.GetServiceAndMethodID(method)
+	}
 	s.P("func (", s.ServerHandler(service), ") GetMethodIDs() []string {")
 	s.P("return []string{")
-	for _, method := range service.Methods {
-		_, methodID := s.GetServiceAndMethodID(method)
+	for _, methodID := range methodIDs {
 		s.P(strconv.Quote(methodID), ",")
 	}
 	s.P("}")
@@ -217,9 +282,8 @@ func (s *srpc) generateService(service *protogen.Service) {
 	s.P("}")
 	s.P()
 	s.P("switch methodID {")
-	for _, method := range service.Methods {
-		_, methodID := s.GetServiceAndMethodID(method)
-		s.P("case ", strconv.Quote(methodID), ":")
+	for i, method := range service.Methods {
+		s.P("case ", strconv.Quote(methodIDs[i]), ":")
 		s.P("return true, d.InvokeMethod_", method.GoName, "(d.impl, strm)")
 	}
 	s.P("default:")
@@ -281,6 +345,10 @@ func (s *srpc) generateService(service *protogen.Service) {
 
 	s.P()
 
+	if s.opts.dispatch {
+		s.generateDispatch(service)
+	}
+
 	// Registration helper
 	s.P("func SRPCRegister", service.GoName, "(mux ", s.Ident(SRPCPackage, "Mux"), ", impl ", s.ServerIface(service), ") error {")
 	s.P("return mux.Register(&", s.ServerHandler(service), "{impl: impl})")
@@ -296,6 +364,42 @@ func (s *srpc) generateService(service *protogen.Service) {
 // client methods
 //
 
+// generateDispatch generates the Dispatch method for unary methods.
+func (s *srpc) generateDispatch(service *protogen.Service) {
+	s.P("// Dispatch invokes a unary method with an encoded request.")
+	s.P("//")
+	s.P("// Returns the encoded response. Returns ", s.Ident(SRPCPackage, "ErrUnimplemented"), " for streaming or unknown methods.")
+	s.P("func (d *", s.ServerHandler(service), ") Dispatch(")
+	s.P("ctx ", s.Ident("context", "Context"), ",")
+	s.P("serviceID, methodID string,")
+	s.P("in []byte,")
+	s.P(") ([]byte, error) {")
+	s.P("if serviceID != \"\" && serviceID != d.GetServiceID() {")
+	s.P("return nil, ", s.Ident(SRPCPackage, "ErrUnimplemented"))
+	s.P("}")
+	s.P()
+	s.P("switch methodID {")
+	for _, method := range service.Methods {
+		if method.Desc.IsStreamingClient() || method.Desc.IsStreamingServer() {
+			continue
+		}
+		_, methodID := s.GetServiceAndMethodID(method)
+		s.P("case ", strconv.Quote(methodID), ":")
+		s.P("req := new(", s.InputType(method), ")")
+		s.P("if err := req.UnmarshalVT(in); err != nil {")
+		s.P("return nil, ", s.Ident("github.com/pkg/errors", "Wrap"), "(", s.Ident(SRPCPackage, "ErrInvalidMessage"), ", err.Error())")
+		s.P("}")
+		s.P("out, err := d.impl.", method.GoName, "(ctx, req)")
+		s.P("if err != nil { return nil, err }")
+		s.P("return out.MarshalVT()")
+	}
+	s.P("default:")
+	s.P("return nil, ", s.Ident(SRPCPackage, "ErrUnimplemented"))
+	s.P("}")
+	s.P("}")
+	s.P()
+}
+
 func (s *srpc) generateClientSignature(method *protogen.Method) string {
 	reqArg := ", in *" + s.InputType(method)
 	if method.Desc.IsStreamingClient() {
@@ -308,6 +412,20 @@ func (s *srpc) generateClientSignature(method *protogen.Method) string {
 	return fmt.Sprintf("%s(ctx %s%s) (%s, error)", method.GoName, s.Ident("context", "Context"), reqArg, respName)
 }
 
+// generateClientSimpleSignature generates the signature of the Simple variant.
+// The empty input or output message is omitted.
+func (s *srpc) generateClientSimpleSignature(method *protogen.Method) string {
+	reqArg := ", in *" + s.InputType(method)
+	if s.IsEmptyMessage(method.Input) {
+		reqArg = ""
+	}
+	ret := "(*" + s.OutputType(method) + ", error)"
+	if s.IsEmptyMessage(method.Output) {
+		ret = "error"
+	}
+	return fmt.Sprintf("%sSimple(ctx %s%s) %s", method.GoName, s.Ident("context", "Context"), reqArg, ret)
+}
+
 func (s *srpc) generateClientMethod(p *protogen.Method) {
 	recvType := s.ClientImpl(p.Parent)
 	outType := s.OutputType(p)
@@ -324,6 +442,22 @@ func (s *srpc) generateClientMethod(p *protogen.Method) {
 		s.P("return out, nil")
 		s.P("}")
 		s.P()
+
+		if s.HasSimpleClientMethod(p) {
+			inRef := "in"
+			if s.IsEmptyMessage(p.Input) {
+				inRef = "new(" + inType + ")"
+			}
+			s.P("func (c *", recvType, ") ", s.generateClientSimpleSignature(p), "{")
+			if s.IsEmptyMessage(p.Output) {
+				s.P("_, err := c.", p.GoName, "(ctx, ", inRef, ")")
+				s.P("return err")
+			} else {
+				s.P("return c.", p.GoName, "(ctx, ", inRef, ")")
+			}
+			s.P("}")
+			s.P()
+		}
 		return
 	}
 
@@ -367,6 +501,12 @@ func (s *srpc) generateClientMethod(p *protogen.Method) {
 	s.P("}")
 	s.P()
 
+	// Unwrap exposes the optional methods of the underlying stream to the srpc helpers.
+	s.P("func (x *", s.ClientStreamImpl(p), ") Unwrap() ", s.Ident(SRPCPackage, "Stream"), " {")
+	s.P("return x.Stream")
+	s.P("}")
+	s.P()
+
 	if genSend {
 		s.P("func (x *", s.ClientStreamImpl(p), ") Send(m *", inType, ") error {")
 		s.P("return x.MsgSend(m)")
@@ -484,6 +624,12 @@ func (s *srpc) generateServerMethod(method *protogen.Method) {
 	s.P("}")
 	s.P()
 
+	// Unwrap exposes the optional methods of the underlying stream to the srpc helpers.
+	s.P("func (x *", s.ServerStreamImpl(method), ") Unwrap() ", s.Ident(SRPCPackage, "Stream"), " {")
+	s.P("return x.Stream")
+	s.P("}")
+	s.P()
+
 	if genSend {
 		s.P("func (x *", s.ServerStreamImpl(method), ") Send(m *", s.OutputType(method), ") error {")
 		s.P("return x.MsgSend(m)")
@@ -493,8 +639,7 @@ func (s *srpc) generateServerMethod(method *protogen.Method) {
 
 	if genSendAndClose {
 		s.P("func (x *", s.ServerStreamImpl(method), ") SendAndClose(m *", s.OutputType(method), ") error {")
-		s.P("if err := x.MsgSend(m); err != nil { return err }")
-		s.P("return x.CloseSend()")
+		s.P("return ", s.Ident(SRPCPackage, "MsgSendComplete"), "(x.Stream, m)")
 		s.P("}")
 		s.P()
 	}
@@ -0,0 +1,323 @@
+package main
+
+import (
+	"go/ast"
+	"go/parser"
+	"go/token"
+	"reflect"
+	"strconv"
+	"strings"
+	"testing"
+
+	"google.golang.org/protobuf/compiler/protogen"
+	"google.golang.org/protobuf/proto"
+	"google.golang.org/protobuf/reflect/protodesc"
+	"google.golang.org/protobuf/types/descriptorpb"
+	"google.golang.org/protobuf/types/known/emptypb"
+	"google.golang.org/protobuf/types/pluginpb"
+)
+
+// newTestMessage constructs a message descriptor with a single string field.
+func newTestMessage(name string) *descriptorpb.DescriptorProto {
+	return &descriptorpb.DescriptorProto{
+		Name: proto.String(name),
+		Field: []*descriptorpb.FieldDescriptorProto{{
+			Name:     proto.String("body"),
+			Number:   proto.Int32(1),
+			Label:    descriptorpb.FieldDescriptorProto_LABEL_OPTIONAL.Enum(),
+			Type:     descriptorpb.FieldDescriptorProto_TYPE_STRING.Enum(),
+			JsonName: proto.String("body"),
+		}},
+	}
+}
+
+// newTestMethod constructs a method descriptor.
+func newTestMethod(name, in, out string, clientStreaming, serverStreaming bool) *descriptorpb.MethodDescriptorProto {
+	return &descriptorpb.MethodDescriptorProto{
+		Name:            proto.String(name),
+		InputType:       proto.String(in),
+		OutputType:      proto.String(out),
+		ClientStreaming: proto.Bool(clientStreaming),
+		ServerStreaming: proto.Bool(serverStreaming),
+	}
+}
+
+// generateTestFile runs the generator against the file & returns the output.
+// deps are the files imported by the file.
+func generateTestFile(t *testing.T, fd *descriptorpb.FileDescriptorProto, deps ...*descriptorpb.FileDescriptorProto) string {
+	return generateTestFileWithOptions(t, &generatorOptions{}, fd, deps...)
+}
+
+// generateTestFileWithOptions runs the generator with the plugin options.
+func generateTestFileWithOptions(
+	t *testing.T,
+	opts *generatorOptions,
+	fd *descriptorpb.FileDescriptorProto,
+	deps ...*descriptorpb.FileDescriptorProto,
+) string {
+	if fd.Options == nil {
+		fd.Options = &descriptorpb.FileOptions{GoPackage: proto.String("example.com/test;test")}
+	}
+	if fd.Syntax == nil {
+		fd.Syntax = proto.String("proto3")
+	}
+	plugin, err := protogen.Options{}.New(&pluginpb.CodeGeneratorRequest{
+		FileToGenerate: []string{fd.GetName()},
+		ProtoFile:      append(deps, fd),
+	})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, f := range plugin.Files {
+		if f.Generate && len(f.Services) != 0 {
+			generatePluginFile(plugin, f, opts)
+		}
+	}
+	resp := plugin.Response()
+	if resp.Error != nil {
+		t.Fatal(resp.GetError())
+	}
+	var out strings.Builder
+	for _, f := range resp.GetFile() {
+		_, _ = out.WriteString(f.GetContent())
+	}
+	return out.String()
+}
+
+// assertContains checks that the generated code contains all of the snippets.
+func assertContains(t *testing.T, out string, snippets ...string) {
+	for _, snippet := range snippets {
+		if !strings.Contains(out, snippet) {
+			t.Errorf("expected generated code to contain %q", snippet)
+		}
+	}
+}
+
+func TestGenerateAsymmetricBidi(t *testing.T) {
+	out := generateTestFile(t, &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/asym.proto"),
+		Package:     proto.String("test"),
+		MessageType: []*descriptorpb.DescriptorProto{newTestMessage("Req"), newTestMessage("Resp")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name:   proto.String("Asym"),
+			Method: []*descriptorpb.MethodDescriptorProto{newTestMethod("Chat", ".test.Req", ".test.Resp", true, true)},
+		}},
+	})
+	assertContains(
+		t, out,
+		// client sends the input type and receives the output type.
+		"func (x *srpcAsym_ChatClient) Send(m *Req) error {",
+		"func (x *srpcAsym_ChatClient) Recv() (*Resp, error) {",
+		"func (x *srpcAsym_ChatClient) RecvTo(m *Resp) error {",
+		// server sends the output type and receives the input type.
+		"func (x *srpcAsym_ChatStream) Send(m *Resp) error {",
+		"func (x *srpcAsym_ChatStream) Recv() (*Req, error) {",
+		"func (x *srpcAsym_ChatStream) RecvTo(m *Req) error {",
+		// both expose the underlying stream to the srpc helpers.
+		"func (x *srpcAsym_ChatClient) Unwrap() srpc.Stream {",
+		"func (x *srpcAsym_ChatStream) Unwrap() srpc.Stream {",
+	)
+}
+
+func TestGenerateEmptyMessageSimpleMethods(t *testing.T) {
+	out := generateTestFile(t, &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/empty.proto"),
+		Package:     proto.String("test"),
+		MessageType: []*descriptorpb.DescriptorProto{{Name: proto.String("Empty")}, newTestMessage("Msg")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name: proto.String("Svc"),
+			Method: []*descriptorpb.MethodDescriptorProto{
+				newTestMethod("DoNothing", ".test.Empty", ".test.Empty", false, false),
+				newTestMethod("Get", ".test.Empty", ".test.Msg", false, false),
+				newTestMethod("Set", ".test.Msg", ".test.Empty", false, false),
+				newTestMethod("Echo", ".test.Msg", ".test.Msg", false, false),
+			},
+		}},
+	})
+	assertContains(
+		t, out,
+		// the full-typed variants are kept.
+		"DoNothing(ctx context.Context, in *Empty) (*Empty, error)",
+		"Get(ctx context.Context, in *Empty) (*Msg, error)",
+		"Set(ctx context.Context, in *Msg) (*Empty, error)",
+		// the simple variants omit the empty messages.
+		"func (c *srpcSvcClient) DoNothingSimple(ctx context.Context) error {",
+		"func (c *srpcSvcClient) GetSimple(ctx context.Context) (*Msg, error) {",
+		"func (c *srpcSvcClient) SetSimple(ctx context.Context, in *Msg) error {",
+	)
+	if strings.Contains(out, "EchoSimple") {
+		t.Error("expected no simple variant without empty messages")
+	}
+}
+
+func TestGenerateEmptyPackage(t *testing.T) {
+	out := generateTestFile(t, &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/nopkg.proto"),
+		MessageType: []*descriptorpb.DescriptorProto{newTestMessage("Msg")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name:   proto.String("Echoer"),
+			Method: []*descriptorpb.MethodDescriptorProto{newTestMethod("Echo", ".Msg", ".Msg", false, false)},
+		}},
+	})
+	assertContains(
+		t, out,
+		// the service id has no package prefix or leading dot, matching TS.
+		`const SRPCEchoerServiceID = "Echoer"`,
+		`c.cc.Invoke(ctx, "Echoer", "Echo", in, out)`,
+	)
+	if strings.Contains(out, `".Echoer"`) {
+		t.Error("expected no leading dot in the service id")
+	}
+}
+
+func TestGenerateWellKnownEmpty(t *testing.T) {
+	emptyFile := protodesc.ToFileDescriptorProto(emptypb.File_google_protobuf_empty_proto)
+	out := generateTestFile(t, &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/wkt.proto"),
+		Package:     proto.String("test"),
+		Dependency:  []string{emptyFile.GetName()},
+		MessageType: []*descriptorpb.DescriptorProto{newTestMessage("Msg")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name: proto.String("Svc"),
+			Method: []*descriptorpb.MethodDescriptorProto{
+				newTestMethod("Ping", ".google.protobuf.Empty", ".google.protobuf.Empty", false, false),
+				newTestMethod("Get", ".google.protobuf.Empty", ".test.Msg", false, false),
+			},
+		}},
+	}, emptyFile)
+	assertContains(
+		t, out,
+		"func (c *srpcSvcClient) PingSimple(ctx context.Context) error {",
+		"func (c *srpcSvcClient) GetSimple(ctx context.Context) (*Msg, error) {",
+	)
+}
+
+func TestGenerateServiceIDOverride(t *testing.T) {
+	serviceIDs := serviceIDOverrides{}
+	if err := serviceIDs.Set("test.v1.Svc=gateway.Svc"); err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, invalid := range []string{"test.v1.Svc", "=gateway.Svc", "test.v1.Svc=", "test.v1.Svc= "} {
+		if err := serviceIDs.Set(invalid); err == nil {
+			t.Errorf("expected error for %q", invalid)
+		}
+	}
+
+	out := generateTestFileWithOptions(t, &generatorOptions{serviceIDs: serviceIDs}, &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/v1/svc.proto"),
+		Package:     proto.String("test.v1"),
+		MessageType: []*descriptorpb.DescriptorProto{newTestMessage("Msg")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name:   proto.String("Svc"),
+			Method: []*descriptorpb.MethodDescriptorProto{newTestMethod("Echo", ".test.v1.Msg", ".test.v1.Msg", false, false)},
+		}},
+	})
+	assertContains(
+		t, out,
+		`const SRPCSvcServiceID = "gateway.Svc"`,
+		`c.cc.Invoke(ctx, "gateway.Svc", "Echo", in, out)`,
+	)
+	if strings.Contains(out, `"test.v1.Svc"`) {
+		t.Error("expected the proto service name to be replaced")
+	}
+}
+
+// parseHandlerMethodIDs parses the generated code and returns the method IDs
+// listed by GetMethodIDs and the method IDs handled by InvokeMethod.
+func parseHandlerMethodIDs(t *testing.T, out string) (listed, handled []string) {
+	f, err := parser.ParseFile(token.NewFileSet(), "", out, 0)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, decl := range f.Decls {
+		fn, ok := decl.(*ast.FuncDecl)
+		if !ok || fn.Recv == nil {
+			continue
+		}
+		var ids *[]string
+		switch fn.Name.Name {
+		case "GetMethodIDs":
+			ids = &listed
+		case "InvokeMethod":
+			ids = &handled
+		default:
+			continue
+		}
+		ast.Inspect(fn.Body, func(n ast.Node) bool {
+			var lits []ast.Expr
+			switch x := n.(type) {
+			case *ast.CompositeLit:
+				lits = x.Elts
+			case *ast.CaseClause:
+				lits = x.List
+			}
+			for _, lit := range lits {
+				if bl, ok := lit.(*ast.BasicLit); ok && bl.Kind == token.STRING {
+					val, _ := strconv.Unquote(bl.Value)
+					*ids = append(*ids, val)
+				}
+			}
+			return true
+		})
+	}
+	return listed, handled
+}
+
+func TestGenerateMethodIDsMatchInvoke(t *testing.T) {
+	out := generateTestFile(t, &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/methods.proto"),
+		Package:     proto.String("test"),
+		MessageType: []*descriptorpb.DescriptorProto{newTestMessage("Msg")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name: proto.String("Svc"),
+			Method: []*descriptorpb.MethodDescriptorProto{
+				newTestMethod("Unary", ".test.Msg", ".test.Msg", false, false),
+				newTestMethod("ServerStream", ".test.Msg", ".test.Msg", false, true),
+				newTestMethod("ClientStream", ".test.Msg", ".test.Msg", true, false),
+				newTestMethod("BidiStream", ".test.Msg", ".test.Msg", true, true),
+			},
+		}},
+	})
+	listed, handled := parseHandlerMethodIDs(t, out)
+	expected := []string{"Unary", "ServerStream", "ClientStream", "BidiStream"}
+	if !reflect.DeepEqual(listed, expected) {
+		t.Fatalf("GetMethodIDs: expected %v got %v", expected, listed)
+	}
+	if !reflect.DeepEqual(handled, expected) {
+		t.Fatalf("InvokeMethod: expected %v got %v", expected, handled)
+	}
+}
+
+func TestGenerateDispatch(t *testing.T) {
+	fd := &descriptorpb.FileDescriptorProto{
+		Name:        proto.String("test/dispatch.proto"),
+		Package:     proto.String("test"),
+		MessageType: []*descriptorpb.DescriptorProto{newTestMessage("Msg")},
+		Service: []*descriptorpb.ServiceDescriptorProto{{
+			Name: proto.String("Svc"),
+			Method: []*descriptorpb.MethodDescriptorProto{
+				newTestMethod("Unary", ".test.Msg", ".test.Msg", false, false),
+				newTestMethod("Stream", ".test.Msg", ".test.Msg", false, true),
+			},
+		}},
+	}
+	if out := generateTestFile(t, fd); strings.Contains(out, "Dispatch(") {
+		t.Error("expected no Dispatch method without the dispatch option")
+	}
+
+	out := generateTestFileWithOptions(t, &generatorOptions{dispatch: true}, fd)
+	assertContains(
+		t, out,
+		"func (d *SRPCSvcHandler) Dispatch(",
+		`case "Unary":`,
+		"out, err := d.impl.Unary(ctx, req)",
+		"return out.MarshalVT()",
+	)
+	// streaming methods use the stream path.
+	if strings.Contains(out, `d.impl.Stream(ctx`) {
+		t.Error("expected no Dispatch case for streaming methods")
+	}
+	if _, err := parser.ParseFile(token.NewFileSet(), "", out, 0); err != nil {
+		t.Fatal(err.Error())
+	}
+}
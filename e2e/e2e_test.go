@@ -204,3 +204,393 @@ func TestE2E_RpcStream(t *testing.T) {
 		return nil
 	})
 }
+
+func TestE2E_FaultyTransport(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := srpc.NewServer(mux)
+
+	// cut the client transport partway through the second server message.
+	openStream := func(ctx context.Context, msgHandler srpc.PacketHandler, closeHandler srpc.CloseHandler) (srpc.Writer, error) {
+		srvPipe, clientPipe := net.Pipe()
+		go func() {
+			_ = server.HandleStream(ctx, srvPipe)
+		}()
+		faulty := srpc.NewFaultyReadWriteCloser(clientPipe, srpc.FaultPlan{CutAfterBytes: 100})
+		prw := srpc.NewPacketReadWriter(faulty)
+		go prw.ReadPump(msgHandler, closeHandler)
+		return prw, nil
+	}
+	client := echo.NewSRPCEchoerClient(srpc.NewClient(openStream))
+
+	ctx := context.Background()
+	strm, err := client.EchoServerStream(ctx, &echo.EchoMsg{Body: "hello world"})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	var rxCount int
+	for {
+		_, err = strm.Recv()
+		if err != nil {
+			break
+		}
+		rxCount++
+	}
+	if !errors.Is(err, srpc.ErrFaultInjected) || !srpc.IsTransportError(err) {
+		t.Fatalf("expected transport error %v got %v", srpc.ErrFaultInjected, err)
+	}
+	if rxCount == 0 || rxCount >= 5 {
+		t.Fatalf("expected the cut mid-stream, got %d messages", rxCount)
+	}
+}
+
+func TestE2E_RawEcho(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := mux.Register(echo.NewRawEchoHandler("smoke.Echo")); err != nil {
+		t.Fatal(err.Error())
+	}
+	client := srpc.NewClient(srpc.NewServerPipe(srpc.NewServer(mux)))
+
+	ctx := context.Background()
+	body := []byte("hello world")
+	out, err := echo.EchoCall(ctx, client, "smoke.Echo", body)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != string(body) {
+		t.Fatalf("expected %q got %q", string(body), string(out))
+	}
+
+	// a truly empty message round-trips with data_is_zero.
+	out, err = echo.EchoCall(ctx, client, "smoke.Echo", nil)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(out) != 0 {
+		t.Fatalf("expected empty message got %q", string(out))
+	}
+}
+
+func TestE2E_SingleStreamBusy(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := srpc.NewServer(mux)
+
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+	srvPipe, clientPipe := net.Pipe()
+	go func() {
+		_ = server.HandleStream(ctx, srvPipe)
+	}()
+	opener := srpc.NewSingleStreamOpener(clientPipe)
+	client := echo.NewSRPCEchoerClient(srpc.NewClient(opener.GetOpenStreamFunc()))
+
+	strm, err := client.EchoBidiStream(ctx)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer strm.Close()
+
+	_, err = client.Echo(ctx, &echo.EchoMsg{Body: "hello world"})
+	if err != srpc.ErrStreamBusy {
+		t.Fatalf("expected %v got %v", srpc.ErrStreamBusy, err)
+	}
+}
+
+func TestE2E_LimitedClient(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	limited := srpc.NewLimitedClient(srpc.NewClient(srpc.NewServerPipe(srpc.NewServer(mux))), 1)
+	client := echo.NewSRPCEchoerClient(limited)
+
+	ctx := context.Background()
+	strm, err := client.EchoBidiStream(ctx)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if n := limited.InFlight(); n != 1 {
+		t.Fatalf("expected 1 in-flight rpc got %d", n)
+	}
+
+	// the limit is reached: the call waits until the context is canceled.
+	waitCtx, waitCtxCancel := context.WithTimeout(ctx, time.Millisecond*50)
+	_, err = client.Echo(waitCtx, &echo.EchoMsg{Body: "hello world"})
+	waitCtxCancel()
+	if err != context.Canceled {
+		t.Fatalf("expected %v got %v", context.Canceled, err)
+	}
+
+	// closing the stream frees the slot.
+	if err := strm.Close(); err != nil {
+		t.Fatal(err.Error())
+	}
+	out, err := client.Echo(ctx, &echo.EchoMsg{Body: "hello world"})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if out.GetBody() != "hello world" {
+		t.Fatalf("expected %q got %q", "hello world", out.GetBody())
+	}
+	if n := limited.InFlight(); n != 0 {
+		t.Fatalf("expected 0 in-flight rpcs got %d", n)
+	}
+}
+
+func TestE2E_ServeListener(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	lis, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+	errCh := make(chan error, 1)
+	go func() {
+		errCh <- srpc.ServeListener(ctx, lis, srpc.NewServer(mux))
+	}()
+
+	// connections are handled concurrently.
+	var clients []echo.SRPCEchoerClient
+	for i := 0; i < 2; i++ {
+		conn, err := net.Dial("tcp", lis.Addr().String())
+		if err != nil {
+			t.Fatal(err.Error())
+		}
+		defer conn.Close()
+		client, err := srpc.NewClientWithConn(conn, true)
+		if err != nil {
+			t.Fatal(err.Error())
+		}
+		echoClient := echo.NewSRPCEchoerClient(client)
+		out, err := echoClient.Echo(ctx, &echo.EchoMsg{Body: "hello world"})
+		if err != nil {
+			t.Fatal(err.Error())
+		}
+		if out.GetBody() != "hello world" {
+			t.Fatalf("expected %q got %q", "hello world", out.GetBody())
+		}
+		clients = append(clients, echoClient)
+	}
+
+	ctxCancel()
+	if err := <-errCh; err != context.Canceled {
+		t.Fatalf("expected %v got %v", context.Canceled, err)
+	}
+
+	// the open connections are closed when ctx is canceled.
+	for _, echoClient := range clients {
+		callCtx, callCtxCancel := context.WithTimeout(context.Background(), time.Second)
+		_, err := echoClient.Echo(callCtx, &echo.EchoMsg{Body: "hello world"})
+		callCtxCancel()
+		if err == nil || err == context.Canceled {
+			t.Fatalf("expected the connection to be closed got %v", err)
+		}
+	}
+}
+
+func TestE2E_HandleStreamWithInfo(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := srpc.NewServer(mux)
+
+	ctx := context.Background()
+	srvPipe, clientPipe := net.Pipe()
+	type info struct {
+		service, method string
+	}
+	infoCh := make(chan info, 1)
+	go func() {
+		service, method, _ := server.HandleStreamWithInfo(ctx, srvPipe)
+		infoCh <- info{service: service, method: method}
+	}()
+	opener := srpc.NewSingleStreamOpener(clientPipe)
+	client := echo.NewSRPCEchoerClient(srpc.NewClient(opener.GetOpenStreamFunc()))
+	if _, err := client.Echo(ctx, &echo.EchoMsg{Body: "hello world"}); err != nil {
+		t.Fatal(err.Error())
+	}
+
+	got := <-infoCh
+	if got.service != echo.SRPCEchoerServiceID || got.method != "Echo" {
+		t.Fatalf("expected %s/Echo got %s/%s", echo.SRPCEchoerServiceID, got.service, got.method)
+	}
+}
+
+func TestE2E_MethodTimeout(t *testing.T) {
+	mux := srpc.NewTimeoutMux(srpc.NewMux())
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	mux.SetMethodTimeout(echo.SRPCEchoerServiceID, "EchoBidiStream", time.Millisecond*50)
+	client := echo.NewSRPCEchoerClient(srpc.NewClient(srpc.NewServerPipe(srpc.NewServer(mux))))
+
+	ctx := context.Background()
+	strm, err := client.EchoBidiStream(ctx)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer strm.Close()
+	// the server sends one message then waits for the client.
+	if _, err := strm.Recv(); err != nil {
+		t.Fatal(err.Error())
+	}
+	_, err = strm.Recv()
+	if err == nil || err.Error() != srpc.ErrMethodTimeout.Error() {
+		t.Fatalf("expected %v got %v", srpc.ErrMethodTimeout, err)
+	}
+
+	// methods without a timeout are not affected.
+	out, err := client.Echo(ctx, &echo.EchoMsg{Body: "hello world"})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if out.GetBody() != "hello world" {
+		t.Fatalf("expected %q got %q", "hello world", out.GetBody())
+	}
+}
+
+func TestE2E_CloseGraceful(t *testing.T) {
+	RunE2E(t, func(client echo.SRPCEchoerClient) error {
+		ctx := context.Background()
+		strm, err := client.EchoBidiStream(ctx)
+		if err != nil {
+			return err
+		}
+		if err := strm.Send(&echo.EchoMsg{Body: "hello world"}); err != nil {
+			return err
+		}
+		if err := srpc.CloseGraceful(strm, time.Second); err != nil {
+			return err
+		}
+		// the messages sent by the server before completing are not lost.
+		for _, expected := range []string{"hello from server", "hello world"} {
+			msg, err := strm.Recv()
+			if err != nil {
+				return err
+			}
+			if msg.GetBody() != expected {
+				return errors.Errorf("expected %q got %q", expected, msg.GetBody())
+			}
+		}
+		if _, err := strm.Recv(); err != io.EOF {
+			return errors.Errorf("expected %v got %v", io.EOF, err)
+		}
+		return nil
+	})
+}
+
+func TestE2E_StreamHelpers(t *testing.T) {
+	RunE2E(t, func(client echo.SRPCEchoerClient) error {
+		strm, err := client.EchoBidiStream(context.Background())
+		if err != nil {
+			return err
+		}
+		defer strm.Close()
+		// the helpers see through the generated stream wrapper.
+		if srpc.StreamRecvClosed(strm) == nil {
+			return errors.New("expected the recv closed channel")
+		}
+		if srpc.IsStreamSendClosed(strm) {
+			return errors.New("expected the send side to be open")
+		}
+		if err := srpc.FlushStream(strm); err != nil {
+			return err
+		}
+		if err := strm.CloseSend(); err != nil {
+			return err
+		}
+		if !srpc.IsStreamSendClosed(strm) {
+			return errors.New("expected the send side to be closed")
+		}
+		select {
+		case <-srpc.StreamRecvClosed(strm):
+		case <-time.After(time.Second):
+			return errors.New("expected the server to complete the rpc")
+		}
+		if !srpc.IsStreamRecvClosed(strm) {
+			return errors.New("expected the recv side to be closed")
+		}
+		return nil
+	})
+}
+
+func TestE2E_Unimplemented(t *testing.T) {
+	mux := srpc.NewMux()
+	if err := echo.SRPCRegisterEchoer(mux, echo.NewEchoServer(mux)); err != nil {
+		t.Fatal(err.Error())
+	}
+	client := srpc.NewClient(srpc.NewServerPipe(srpc.NewServer(mux)))
+
+	ctx := context.Background()
+	err := client.Invoke(ctx, echo.SRPCEchoerServiceID, "Unknown", &echo.EchoMsg{}, &echo.EchoMsg{})
+	if err != srpc.ErrUnimplemented {
+		t.Fatalf("expected %v got %v", srpc.ErrUnimplemented, err)
+	}
+}
+
+func TestE2E_ServeStream(t *testing.T) {
+	mux := srpc.NewMux()
+	srvErr := errors.New("source failed")
+	handler := &serveStreamHandler{msgs: []string{"a", "b", "c"}, err: srvErr}
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	client := srpc.NewClient(srpc.NewServerPipe(srpc.NewServer(mux)))
+
+	ctx := context.Background()
+	strm, err := srpc.NewServerStream(ctx, client, "test.ServeStream", "List", &echo.EchoMsg{})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer strm.Close()
+	for _, expected := range handler.msgs {
+		msg := &echo.EchoMsg{}
+		if err := strm.MsgRecv(msg); err != nil {
+			t.Fatal(err.Error())
+		}
+		if msg.GetBody() != expected {
+			t.Fatalf("expected %q got %q", expected, msg.GetBody())
+		}
+	}
+	if err := strm.MsgRecv(&echo.EchoMsg{}); err == nil || err.Error() != srvErr.Error() {
+		t.Fatalf("expected %v got %v", srvErr, err)
+	}
+}
+
+// serveStreamHandler streams msgs with ServeStream then returns err.
+type serveStreamHandler struct {
+	msgs []string
+	err  error
+}
+
+// GetServiceID returns the ID of the service.
+func (h *serveStreamHandler) GetServiceID() string { return "test.ServeStream" }
+
+// GetMethodIDs returns the list of methods for the service.
+func (h *serveStreamHandler) GetMethodIDs() []string { return []string{"List"} }
+
+// InvokeMethod invokes the method matching the service & method ID.
+func (h *serveStreamHandler) InvokeMethod(serviceID, methodID string, strm srpc.Stream) (bool, error) {
+	if err := strm.MsgRecv(&echo.EchoMsg{}); err != nil {
+		return true, err
+	}
+	var i int
+	return true, srpc.ServeStream(strm, func() (*echo.EchoMsg, error) {
+		if i == len(h.msgs) {
+			return nil, h.err
+		}
+		i++
+		return &echo.EchoMsg{Body: h.msgs[i-1]}, nil
+	})
+}
@@ -62,6 +62,10 @@ type srpcEchoer_EchoServerStreamClient struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoServerStreamClient) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoServerStreamClient) Recv() (*EchoMsg, error) {
 	m := new(EchoMsg)
 	if err := x.MsgRecv(m); err != nil {
@@ -93,6 +97,10 @@ type srpcEchoer_EchoClientStreamClient struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoClientStreamClient) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoClientStreamClient) Send(m *EchoMsg) error {
 	return x.MsgSend(m)
 }
@@ -135,6 +143,10 @@ type srpcEchoer_EchoBidiStreamClient struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoBidiStreamClient) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoBidiStreamClient) Send(m *EchoMsg) error {
 	return x.MsgSend(m)
 }
@@ -171,6 +183,10 @@ type srpcEchoer_RpcStreamClient struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_RpcStreamClient) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_RpcStreamClient) Send(m *rpcstream.RpcStreamPacket) error {
 	return x.MsgSend(m)
 }
@@ -308,11 +324,12 @@ type srpcEchoer_EchoStream struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoStream) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoStream) SendAndClose(m *EchoMsg) error {
-	if err := x.MsgSend(m); err != nil {
-		return err
-	}
-	return x.CloseSend()
+	return srpc.MsgSendComplete(x.Stream, m)
 }
 
 type SRPCEchoer_EchoServerStreamStream interface {
@@ -324,6 +341,10 @@ type srpcEchoer_EchoServerStreamStream struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoServerStreamStream) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoServerStreamStream) Send(m *EchoMsg) error {
 	return x.MsgSend(m)
 }
@@ -338,11 +359,12 @@ type srpcEchoer_EchoClientStreamStream struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoClientStreamStream) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoClientStreamStream) SendAndClose(m *EchoMsg) error {
-	if err := x.MsgSend(m); err != nil {
-		return err
-	}
-	return x.CloseSend()
+	return srpc.MsgSendComplete(x.Stream, m)
 }
 
 func (x *srpcEchoer_EchoClientStreamStream) Recv() (*EchoMsg, error) {
@@ -367,6 +389,10 @@ type srpcEchoer_EchoBidiStreamStream struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_EchoBidiStreamStream) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_EchoBidiStreamStream) Send(m *EchoMsg) error {
 	return x.MsgSend(m)
 }
@@ -393,6 +419,10 @@ type srpcEchoer_RpcStreamStream struct {
 	srpc.Stream
 }
 
+func (x *srpcEchoer_RpcStreamStream) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcEchoer_RpcStreamStream) Send(m *rpcstream.RpcStreamPacket) error {
 	return x.MsgSend(m)
 }
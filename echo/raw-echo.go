@@ -0,0 +1,81 @@
+package echo
+
+import (
+	"context"
+	"io"
+
+	srpc "github.com/aperturerobotics/starpc/srpc"
+)
+
+const (
+	// RawEchoMethodEcho is the unary echo method of RawEchoHandler.
+	RawEchoMethodEcho = "Echo"
+	// RawEchoMethodEchoBidiStream is the bidi echo method of RawEchoHandler.
+	RawEchoMethodEchoBidiStream = "EchoBidiStream"
+)
+
+// RawEchoHandler is a Handler which echoes raw messages under a service ID.
+//
+// Intended for smoke-testing a transport or deployment without any generated
+// code: the messages are echoed back without being decoded.
+type RawEchoHandler struct {
+	// serviceID is the service id
+	serviceID string
+}
+
+// NewRawEchoHandler constructs a new RawEchoHandler with a service ID.
+func NewRawEchoHandler(serviceID string) *RawEchoHandler {
+	return &RawEchoHandler{serviceID: serviceID}
+}
+
+// GetServiceID returns the ID of the service.
+func (h *RawEchoHandler) GetServiceID() string {
+	return h.serviceID
+}
+
+// GetMethodIDs returns the list of methods for the service.
+func (h *RawEchoHandler) GetMethodIDs() []string {
+	return []string{RawEchoMethodEcho, RawEchoMethodEchoBidiStream}
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+// Returns false, nil if not found.
+// If service string is empty, ignore it.
+func (h *RawEchoHandler) InvokeMethod(serviceID, methodID string, strm srpc.Stream) (bool, error) {
+	if serviceID != "" && serviceID != h.serviceID {
+		return false, nil
+	}
+
+	switch methodID {
+	case RawEchoMethodEcho:
+		msg := new(srpc.RawMessage)
+		if err := strm.MsgRecv(msg); err != nil {
+			return true, err
+		}
+		return true, strm.MsgSend(msg)
+	case RawEchoMethodEchoBidiStream:
+		for {
+			msg := new(srpc.RawMessage)
+			if err := strm.MsgRecv(msg); err != nil {
+				if err == io.EOF {
+					return true, nil
+				}
+				return true, err
+			}
+			if err := strm.MsgSend(msg); err != nil {
+				return true, err
+			}
+		}
+	default:
+		return false, nil
+	}
+}
+
+// EchoCall calls the unary Echo method of a RawEchoHandler.
+// Returns the echoed message data.
+func EchoCall(ctx context.Context, client srpc.Client, serviceID string, body []byte) ([]byte, error) {
+	return srpc.InvokeRaw(ctx, client, serviceID, RawEchoMethodEcho, body)
+}
+
+// _ is a type assertion
+var _ srpc.Handler = ((*RawEchoHandler)(nil))
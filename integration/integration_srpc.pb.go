@@ -47,6 +47,10 @@ type srpcIntegrationService_RpcStreamClient struct {
 	srpc.Stream
 }
 
+func (x *srpcIntegrationService_RpcStreamClient) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcIntegrationService_RpcStreamClient) Send(m *rpcstream.RpcStreamPacket) error {
 	return x.MsgSend(m)
 }
@@ -122,6 +126,10 @@ type srpcIntegrationService_RpcStreamStream struct {
 	srpc.Stream
 }
 
+func (x *srpcIntegrationService_RpcStreamStream) Unwrap() srpc.Stream {
+	return x.Stream
+}
+
 func (x *srpcIntegrationService_RpcStreamStream) Send(m *rpcstream.RpcStreamPacket) error {
 	return x.MsgSend(m)
 }
@@ -129,7 +129,9 @@ func HandleRpcStream(stream RpcStream, getter RpcStreamGetter) error {
 	prw := srpc.NewPacketReadWriter(srw)
 	serverRPC.SetWriter(prw)
 	go prw.ReadPump(serverRPC.HandlePacket, serverRPC.HandleStreamClose)
-	return serverRPC.Wait(ctx)
+	err = serverRPC.Wait(ctx)
+	serverRPC.WaitTeardown()
+	return err
 }
 
 // RpcStreamReadWriter reads and writes a buffered RpcStream.
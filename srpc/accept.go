@@ -3,17 +3,41 @@ package srpc
 import (
 	"context"
 	"net"
+
+	"github.com/libp2p/go-libp2p-core/network"
 )
 
 // AcceptMuxedListener accepts incoming connections from a net.Listener.
 //
+// Handles each connection in a separate goroutine until the connection is
+// closed or ctx is canceled.
 // Uses the default mplex muxer.
 func AcceptMuxedListener(ctx context.Context, lis net.Listener, srv *Server) error {
 	for {
-		nc, err := lis.Accept()
+		serveConn, err := AcceptListenerConn(ctx, lis, srv)
 		if err != nil {
 			return err
 		}
+		go func() {
+			_ = serveConn()
+		}()
+	}
+}
+
+// AcceptListenerConn accepts the next incoming connection from a net.Listener.
+//
+// Returns a function which serves the connection with the Server until it is
+// closed or ctx is canceled, then closes the connection. Returns
+// context.Canceled if ctx was canceled. The caller decides
+// how to run it, for example to manage the goroutines of an accept loop.
+// Connections which fail to set up the muxer are closed and skipped.
+// Uses the default mplex muxer.
+func AcceptListenerConn(ctx context.Context, lis net.Listener, srv *Server) (func() error, error) {
+	for {
+		nc, err := lis.Accept()
+		if err != nil {
+			return nil, err
+		}
 
 		mc, err := NewMuxedConn(nc, false)
 		if err != nil {
@@ -21,9 +45,65 @@ func AcceptMuxedListener(ctx context.Context, lis net.Listener, srv *Server) err
 			continue
 		}
 
-		if err := srv.AcceptMuxedConn(ctx, mc); err != nil {
-			_ = nc.Close()
-			continue
+		connCtx := WithConnInfo(ctx, newConnInfo(nc))
+		return func() error {
+			defer nc.Close()
+			return serveMuxedConn(connCtx, mc, srv)
+		}, nil
+	}
+}
+
+// serveMuxedConn serves the muxed connection until it is closed or ctx is
+// canceled. Closes the connection when ctx is canceled to unblock AcceptStream.
+func serveMuxedConn(ctx context.Context, mc network.MuxedConn, srv *Server) error {
+	done := make(chan struct{})
+	defer close(done)
+	go func() {
+		select {
+		case <-ctx.Done():
+			_ = mc.Close()
+		case <-done:
+		}
+	}()
+
+	err := srv.AcceptMuxedConn(ctx, mc)
+	if ctx.Err() != nil {
+		return context.Canceled
+	}
+	return err
+}
+
+// ListenAndServe listens on the network address and serves incoming
+// connections with the Server until ctx is canceled.
+//
+// network is any network supported by net.Listen, for example "tcp" or "unix".
+// Uses the default mplex muxer.
+func ListenAndServe(ctx context.Context, network, addr string, srv *Server) error {
+	lis, err := net.Listen(network, addr)
+	if err != nil {
+		return err
+	}
+	return ServeListener(ctx, lis, srv)
+}
+
+// ServeListener serves incoming connections from a net.Listener until ctx is
+// canceled, then closes the listener and the accepted connections.
+//
+// Returns context.Canceled if ctx was canceled.
+func ServeListener(ctx context.Context, lis net.Listener, srv *Server) error {
+	done := make(chan struct{})
+	defer close(done)
+	go func() {
+		select {
+		case <-ctx.Done():
+		case <-done:
 		}
+		_ = lis.Close()
+	}()
+
+	err := AcceptMuxedListener(ctx, lis, srv)
+	if ctx.Err() != nil {
+		return context.Canceled
 	}
+	return err
 }
@@ -0,0 +1,94 @@
+package srpc
+
+import (
+	"context"
+	"net"
+	"testing"
+	"time"
+)
+
+func TestAcceptListenerConn(t *testing.T) {
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	lis, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer lis.Close()
+
+	errCh := make(chan error, 1)
+	go func() {
+		serveConn, err := AcceptListenerConn(ctx, lis, NewServer(mux))
+		if err != nil {
+			errCh <- err
+			return
+		}
+		errCh <- serveConn()
+	}()
+
+	nc, err := net.Dial("tcp", lis.Addr().String())
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	client, err := NewClientWithConn(nc, true)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	in, out := RawMessage("hello"), new(RawMessage)
+	if err := client.Invoke(ctx, interopService, "Unary", &in, out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(*out) != "hello" {
+		t.Fatalf("expected hello got %q", string(*out))
+	}
+
+	// the connection is served until the client closes it.
+	_ = nc.Close()
+	if err := <-errCh; err == nil {
+		t.Fatal("expected an error after the connection was closed")
+	}
+}
+
+func TestAcceptListenerConn_Cancel(t *testing.T) {
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+	lis, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer lis.Close()
+
+	errCh := make(chan error, 1)
+	go func() {
+		serveConn, err := AcceptListenerConn(ctx, lis, NewServer(NewMux()))
+		if err != nil {
+			errCh <- err
+			return
+		}
+		errCh <- serveConn()
+	}()
+
+	nc, err := net.Dial("tcp", lis.Addr().String())
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer nc.Close()
+	if _, err := NewClientWithConn(nc, true); err != nil {
+		t.Fatal(err.Error())
+	}
+
+	// the connection stays open: canceling ctx ends serving it.
+	ctxCancel()
+	select {
+	case err := <-errCh:
+		if err != context.Canceled {
+			t.Fatalf("expected %v got %v", context.Canceled, err)
+		}
+	case <-time.After(time.Second):
+		t.Fatal("expected serveConn to return after ctx was canceled")
+	}
+}
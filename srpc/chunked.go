@@ -0,0 +1,101 @@
+package srpc
+
+import (
+	"encoding/binary"
+)
+
+// DefaultChunkSize is the default chunk size in bytes for MsgSendChunked.
+const DefaultChunkSize = 1024 * 1024
+
+// DefaultChunkedMaxSize is the default max size in bytes of a message received
+// with MsgRecvChunked.
+const DefaultChunkedMaxSize = 64 * 1024 * 1024
+
+// chunkHeaderSize is the size of the chunk envelope header in bytes.
+const chunkHeaderSize = 8
+
+// MsgSendChunked encodes the message and sends it as one or more chunks.
+//
+// Use to send messages larger than the max message size. The remote must
+// receive the message with MsgRecvChunked.
+//
+// Each chunk is sent as a separate message with an envelope:
+//
+//	[0:4] LittleEndian uint32 index of the chunk, starting at 0
+//	[4:8] LittleEndian uint32 total number of chunks, at least 1
+//	[8:]  chunk of the encoded message
+//
+// The chunk with index total-1 is the final chunk. An empty message is sent as
+// a single chunk with no data.
+//
+// If chunkSize <= 0, uses DefaultChunkSize.
+func MsgSendChunked(strm Stream, msg Message, chunkSize int) error {
+	if chunkSize <= 0 {
+		chunkSize = DefaultChunkSize
+	}
+	data, err := msg.MarshalVT()
+	if err != nil {
+		return err
+	}
+	total := (len(data) + chunkSize - 1) / chunkSize
+	if total == 0 {
+		total = 1
+	}
+	for i := 0; i < total; i++ {
+		start, end := i*chunkSize, (i+1)*chunkSize
+		if end > len(data) {
+			end = len(data)
+		}
+		chunk := make(RawMessage, chunkHeaderSize+end-start)
+		binary.LittleEndian.PutUint32(chunk, uint32(i))
+		binary.LittleEndian.PutUint32(chunk[4:], uint32(total))
+		copy(chunk[chunkHeaderSize:], data[start:end])
+		if err := strm.MsgSend(&chunk); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// MsgRecvChunked receives a message sent with MsgSendChunked.
+//
+// Reassembles the chunks and parses the message into the object at msg.
+// Returns ErrInvalidChunk if a chunk is malformed or out of order, or
+// ErrMessageTooLarge if the message would exceed maxSize bytes.
+//
+// If maxSize <= 0, uses DefaultChunkedMaxSize.
+func MsgRecvChunked(strm Stream, msg Message, maxSize int) error {
+	if maxSize <= 0 {
+		maxSize = DefaultChunkedMaxSize
+	}
+	var data []byte
+	var total uint32
+	for idx := uint32(0); ; idx++ {
+		var chunk RawMessage
+		if err := strm.MsgRecv(&chunk); err != nil {
+			return err
+		}
+		if len(chunk) < chunkHeaderSize {
+			return ErrInvalidChunk
+		}
+		chunkIdx := binary.LittleEndian.Uint32(chunk)
+		chunkTotal := binary.LittleEndian.Uint32(chunk[4:])
+		if chunkIdx != idx || chunkTotal == 0 || (idx != 0 && chunkTotal != total) {
+			return ErrInvalidChunk
+		}
+		// each chunk of a multi-chunk message carries at least one byte.
+		payload := chunk[chunkHeaderSize:]
+		if chunkTotal > 1 && len(payload) == 0 {
+			return ErrInvalidChunk
+		}
+		if (chunkTotal > 1 && uint64(chunkTotal) > uint64(maxSize)) || len(data)+len(payload) > maxSize {
+			return ErrMessageTooLarge
+		}
+		total = chunkTotal
+		data = append(data, payload...)
+		if idx+1 == total {
+			break
+		}
+	}
+	return msg.UnmarshalVT(data)
+}
@@ -0,0 +1,68 @@
+package srpc
+
+import (
+	"bytes"
+	"context"
+	"testing"
+)
+
+func TestMsgChunked(t *testing.T) {
+	ctx := context.Background()
+	for _, body := range [][]byte{[]byte("hello world"), nil} {
+		s1, s2 := NewPipeStream(ctx)
+		errCh := make(chan error, 1)
+		go func() {
+			msg := RawMessage(body)
+			errCh <- MsgSendChunked(s1, &msg, 3)
+		}()
+		var out RawMessage
+		if err := MsgRecvChunked(s2, &out, 0); err != nil {
+			t.Fatal(err.Error())
+		}
+		if err := <-errCh; err != nil {
+			t.Fatal(err.Error())
+		}
+		if !bytes.Equal(out, body) {
+			t.Fatalf("expected %q got %q", string(body), string(out))
+		}
+	}
+}
+
+func TestMsgRecvChunked_OutOfOrder(t *testing.T) {
+	s1, s2 := NewPipeStream(context.Background())
+	// index 1 of 2 without index 0.
+	chunk := RawMessage{1, 0, 0, 0, 2, 0, 0, 0, 'a'}
+	if err := s1.MsgSend(&chunk); err != nil {
+		t.Fatal(err.Error())
+	}
+	var out RawMessage
+	if err := MsgRecvChunked(s2, &out, 0); err != ErrInvalidChunk {
+		t.Fatalf("expected %v got %v", ErrInvalidChunk, err)
+	}
+}
+
+func TestMsgRecvChunked_MaxSize(t *testing.T) {
+	ctx := context.Background()
+	s1, s2 := NewPipeStream(ctx)
+	errCh := make(chan error, 1)
+	go func() {
+		msg := RawMessage("hello world")
+		errCh <- MsgSendChunked(s1, &msg, 3)
+	}()
+	var out RawMessage
+	if err := MsgRecvChunked(s2, &out, 8); err != ErrMessageTooLarge {
+		t.Fatalf("expected %v got %v", ErrMessageTooLarge, err)
+	}
+	_ = s2.Close()
+	<-errCh
+
+	// the total number of chunks is bounded before buffering any data.
+	s1, s2 = NewPipeStream(ctx)
+	chunk := RawMessage{0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 'a'}
+	if err := s1.MsgSend(&chunk); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := MsgRecvChunked(s2, &out, 0); err != ErrMessageTooLarge {
+		t.Fatalf("expected %v got %v", ErrMessageTooLarge, err)
+	}
+}
@@ -2,7 +2,10 @@ package srpc
 
 import (
 	"context"
+	"fmt"
 	"io"
+	"sync"
+	"time"
 
 	"github.com/pkg/errors"
 )
@@ -14,7 +17,10 @@ type ClientRPC struct {
 	ctx context.Context
 	// ctxCancel is called when the rpc ends.
 	ctxCancel context.CancelFunc
+	// writerMtx guards writer
+	writerMtx sync.Mutex
 	// writer is the writer to write messages to
+	// set by Start, may be nil before.
 	writer Writer
 	// service is the rpc service
 	service string
@@ -26,10 +32,16 @@ type ClientRPC struct {
 	// dataChClosed is a flag set after dataCh is closed.
 	// controlled by HandlePacket.
 	dataChClosed bool
+	// recvClosed is closed when dataCh is closed.
+	recvClosed chan struct{}
 	// serverErr is an error set by the client.
 	// before dataCh is closed, managed by HandlePacket.
 	// immutable after dataCh is closed.
 	serverErr error
+	// maxResponseSize is the max size of a single response message, if > 0
+	maxResponseSize int
+	// recvStallTimeout is the max time to wait for a free slot in dataCh, if > 0
+	recvStallTimeout time.Duration
 }
 
 // NewClientRPC constructs a new ClientRPC session and writes CallStart.
@@ -38,16 +50,84 @@ type ClientRPC struct {
 // must call Start after creating the RPC object.
 func NewClientRPC(ctx context.Context, service, method string) *ClientRPC {
 	rpc := &ClientRPC{
-		service: service,
-		method:  method,
-		dataCh:  make(chan []byte, 5),
+		service:    service,
+		method:     method,
+		dataCh:     newRecvBuffer(0),
+		recvClosed: make(chan struct{}),
 	}
 	rpc.ctx, rpc.ctxCancel = context.WithCancel(ctx)
+	rpc.maxResponseSize, _ = GetMaxResponseSize(ctx)
+	rpc.recvStallTimeout, _ = GetRecvStallTimeout(ctx)
 	return rpc
 }
 
+// maxResponseSizeCtxKey is the context key for the max response size.
+type maxResponseSizeCtxKey struct{}
+
+// WithMaxResponseSize attaches the max size of a single response message to the
+// context, limiting the responses of rpcs started with the context.
+//
+// Responses larger than size end the rpc with ErrMessageTooLarge. The max
+// message size of the transport applies regardless.
+func WithMaxResponseSize(ctx context.Context, size int) context.Context {
+	return context.WithValue(ctx, maxResponseSizeCtxKey{}, size)
+}
+
+// GetMaxResponseSize returns the max response size attached to the context, if any.
+func GetMaxResponseSize(ctx context.Context) (int, bool) {
+	size, ok := ctx.Value(maxResponseSizeCtxKey{}).(int)
+	return size, ok && size > 0
+}
+
+// recvStallTimeoutCtxKey is the context key for the recv stall timeout.
+type recvStallTimeoutCtxKey struct{}
+
+// WithRecvStallTimeout attaches the recv stall timeout to the context, applying
+// it to the rpcs started with the context.
+//
+// See ClientRPC.SetRecvStallTimeout.
+func WithRecvStallTimeout(ctx context.Context, timeout time.Duration) context.Context {
+	return context.WithValue(ctx, recvStallTimeoutCtxKey{}, timeout)
+}
+
+// GetRecvStallTimeout returns the recv stall timeout attached to the context, if any.
+func GetRecvStallTimeout(ctx context.Context) (time.Duration, bool) {
+	timeout, ok := ctx.Value(recvStallTimeoutCtxKey{}).(time.Duration)
+	return timeout, ok && timeout > 0
+}
+
+// SetRecvStallTimeout sets the max time to wait for the caller to read a
+// message when the recv buffer is full.
+//
+// While the buffer is full the read pump is blocked and no packets are handled
+// for the stream. If the buffer stays full for longer than timeout, the rpc
+// ends with ErrRecvStalled. Zero or less waits indefinitely (the default).
+// The buffer size can be increased with SetRecvBufferSize.
+func (r *ClientRPC) SetRecvStallTimeout(timeout time.Duration) {
+	r.recvStallTimeout = timeout
+}
+
+// SetMaxResponseSize sets the max size of a single response message.
+//
+// Responses larger than size end the rpc with ErrMessageTooLarge. Zero or less
+// is unlimited. Defaults to the size attached with WithMaxResponseSize.
+func (r *ClientRPC) SetMaxResponseSize(size int) {
+	r.maxResponseSize = size
+}
+
+// SetRecvBufferSize sets the number of incoming messages to buffer.
+//
+// The read pump blocks when the buffer is full until the caller reads the
+// messages. If size <= 0 uses DefaultRecvBufferSize.
+// Must be called before Start.
+func (r *ClientRPC) SetRecvBufferSize(size int) {
+	r.dataCh = newRecvBuffer(size)
+}
+
 // Start sets the writer and writes the MsgSend message.
 // must only be called once!
+//
+// Returns ErrMessageTooLarge if the CallStart packet exceeds the max message size.
 func (r *ClientRPC) Start(writer Writer, writeFirstMsg bool, firstMsg []byte) error {
 	select {
 	case <-r.ctx.Done():
@@ -55,7 +135,9 @@ func (r *ClientRPC) Start(writer Writer, writeFirstMsg bool, firstMsg []byte) er
 		return context.Canceled
 	default:
 	}
+	r.writerMtx.Lock()
 	r.writer = writer
+	r.writerMtx.Unlock()
 	var firstMsgEmpty bool
 	if writeFirstMsg {
 		firstMsgEmpty = len(firstMsg) == 0
@@ -63,6 +145,10 @@ func (r *ClientRPC) Start(writer Writer, writeFirstMsg bool, firstMsg []byte) er
 		firstMsg = nil
 	}
 	pkt := NewCallStartPacket(r.service, r.method, firstMsg, firstMsgEmpty)
+	if pkt.SizeVT() > int(maxMessageSize) {
+		r.Close()
+		return ErrMessageTooLarge
+	}
 	if err := writer.WritePacket(pkt); err != nil {
 		r.Close()
 		return err
@@ -91,18 +177,41 @@ func (r *ClientRPC) ReadAll() ([][]byte, error) {
 //
 // returns io.EOF if the stream ended.
 func (r *ClientRPC) ReadOne() ([]byte, error) {
+	return r.ReadOneContext(r.ctx)
+}
+
+// ReadOneContext reads a single message and returns.
+//
+// Messages received before the cancel are returned first, followed by the
+// error or io.EOF if the remote completed the rpc.
+//
+// returns context.Canceled if ctx or the rpc is canceled.
+// returns io.EOF if the stream ended.
+func (r *ClientRPC) ReadOneContext(ctx context.Context) ([]byte, error) {
+	select {
+	case data, ok := <-r.dataCh:
+		return r.handleRecvData(data, ok)
+	default:
+	}
 	select {
+	case <-ctx.Done():
+		return nil, context.Canceled
 	case <-r.ctx.Done():
 		return nil, context.Canceled
 	case data, ok := <-r.dataCh:
-		if !ok {
-			if err := r.serverErr; err != nil {
-				return nil, err
-			}
-			return nil, io.EOF
+		return r.handleRecvData(data, ok)
+	}
+}
+
+// handleRecvData returns the data read from dataCh, or the error if closed.
+func (r *ClientRPC) handleRecvData(data []byte, ok bool) ([]byte, error) {
+	if !ok {
+		if err := r.serverErr; err != nil {
+			return nil, err
 		}
-		return data, nil
+		return nil, io.EOF
 	}
+	return data, nil
 }
 
 // Context is canceled when the ClientRPC is no longer valid.
@@ -121,13 +230,35 @@ func (r *ClientRPC) HandlePacketData(data []byte) error {
 }
 
 // HandleStreamClose handles the incoming stream closing w/ optional error.
+//
+// closeErr is nil or io.EOF if the remote closed the stream, context.Canceled
+// if the stream was closed locally, or the transport error otherwise.
+//
+// If the rpc was not completed, any pending reads return context.Canceled if
+// the stream was closed locally, otherwise a TransportError wrapping closeErr,
+// or io.ErrUnexpectedEOF if the remote closed the stream.
+//
+// Closes the writer if the stream failed. After a clean close the writer is
+// closed by Close.
 func (r *ClientRPC) HandleStreamClose(closeErr error) {
-	if closeErr != nil {
-		if r.serverErr == nil {
+	if r.dataChClosed {
+		return
+	}
+	cleanClose := closeErr == nil || closeErr == io.EOF
+	if cleanClose {
+		closeErr = io.ErrUnexpectedEOF
+	}
+	if r.serverErr == nil {
+		if closeErr == context.Canceled {
 			r.serverErr = closeErr
+		} else {
+			r.serverErr = &TransportError{Err: closeErr}
 		}
-		r.Close()
 	}
+	if !cleanClose {
+		r.closeWriter()
+	}
+	r.closeDataCh()
 }
 
 // HandlePacket handles an incoming parsed message packet.
@@ -147,10 +278,29 @@ func (r *ClientRPC) HandlePacket(msg *Packet) error {
 	}
 }
 
+// HandleProtocolError ends the rpc with a protocol error from the remote.
+//
+// Any pending reads return err instead of a TransportError.
+// Not concurrency safe: use a mutex if calling concurrently.
+func (r *ClientRPC) HandleProtocolError(err error) {
+	if r.dataChClosed {
+		return
+	}
+	if r.serverErr == nil {
+		r.serverErr = err
+	}
+	r.closeWriter()
+	r.closeDataCh()
+}
+
 // HandleCallStart handles the call start packet.
+//
+// Server-to-client calls are not supported: ends the rpc with
+// ErrUnrecognizedPacket.
 func (r *ClientRPC) HandleCallStart(pkt *CallStart) error {
-	// server-to-client calls not supported
-	return errors.Wrap(ErrUnrecognizedPacket, "call start packet unexpected")
+	err := errors.Wrap(ErrUnrecognizedPacket, "call start packet unexpected")
+	r.HandleProtocolError(err)
+	return err
 }
 
 // HandleCallData handles the call data packet.
@@ -160,30 +310,91 @@ func (r *ClientRPC) HandleCallData(pkt *CallData) error {
 	}
 
 	if data := pkt.GetData(); len(data) != 0 || pkt.GetDataIsZero() {
-		select {
-		case <-r.ctx.Done():
-			return context.Canceled
-		case r.dataCh <- data:
+		if r.maxResponseSize > 0 && len(data) > r.maxResponseSize {
+			r.HandleProtocolError(ErrMessageTooLarge)
+			return ErrMessageTooLarge
+		}
+		if err := r.pushData(data); err != nil {
+			return err
 		}
 	}
 
 	complete := pkt.GetComplete()
 	if err := pkt.GetError(); len(err) != 0 {
 		complete = true
-		r.serverErr = errors.New(err)
+		r.serverErr = ErrorFromString(err)
 	}
 
 	if complete {
-		r.dataChClosed = true
-		close(r.dataCh)
+		r.closeDataCh()
 	}
 
 	return nil
 }
 
+// pushData pushes incoming data to dataCh, waiting for a free slot.
+// Ends the rpc with ErrRecvStalled if the recv stall timeout is exceeded.
+func (r *ClientRPC) pushData(data []byte) error {
+	select {
+	case r.dataCh <- data:
+		return nil
+	default:
+	}
+
+	var stallCh <-chan time.Time
+	if r.recvStallTimeout > 0 {
+		stallTimer := time.NewTimer(r.recvStallTimeout)
+		defer stallTimer.Stop()
+		stallCh = stallTimer.C
+	}
+	select {
+	case <-r.ctx.Done():
+		return context.Canceled
+	case r.dataCh <- data:
+		return nil
+	case <-stallCh:
+		r.HandleProtocolError(ErrRecvStalled)
+		return ErrRecvStalled
+	}
+}
+
+// PendingRecv returns the number of buffered incoming messages not yet read.
+// Safe to call concurrently.
+func (r *ClientRPC) PendingRecv() int {
+	return len(r.dataCh)
+}
+
+// RecvClosed returns a channel closed when the remote closed its send side.
+func (r *ClientRPC) RecvClosed() <-chan struct{} {
+	return r.recvClosed
+}
+
+// String returns a description of the rpc for debugging.
+// Safe to call concurrently.
+func (r *ClientRPC) String() string {
+	return fmt.Sprintf("ClientRPC(%s/%s recv_closed=%v)", r.service, r.method, isChanClosed(r.recvClosed))
+}
+
+// closeDataCh closes the data channel and signals recvClosed.
+func (r *ClientRPC) closeDataCh() {
+	r.dataChClosed = true
+	close(r.dataCh)
+	close(r.recvClosed)
+}
+
+// closeWriter closes the writer, if set.
+func (r *ClientRPC) closeWriter() {
+	r.writerMtx.Lock()
+	writer := r.writer
+	r.writerMtx.Unlock()
+	if writer != nil {
+		_ = writer.Close()
+	}
+}
+
 // Close releases any resources held by the ClientRPC.
 // not concurrency safe with HandlePacket.
 func (r *ClientRPC) Close() {
 	r.ctxCancel()
-	_ = r.writer.Close()
+	r.closeWriter()
 }
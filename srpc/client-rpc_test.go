@@ -0,0 +1,171 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"io"
+	"testing"
+	"time"
+)
+
+func TestClientRPC_TransportError(t *testing.T) {
+	ctx := context.Background()
+	readErr := errors.New("connection reset")
+	for _, closeErr := range []error{nil, readErr} {
+		rpc := NewClientRPC(ctx, "test.Service", "Method")
+		if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+			t.Fatal(err.Error())
+		}
+		rpc.HandleStreamClose(closeErr)
+		_, err := rpc.ReadOne()
+		if !IsTransportError(err) {
+			t.Fatalf("expected transport error got %v", err)
+		}
+		expected := closeErr
+		if expected == nil {
+			expected = io.ErrUnexpectedEOF
+		}
+		if !errors.Is(err, expected) {
+			t.Fatalf("expected %v got %v", expected, err)
+		}
+	}
+
+	// closing the stream locally cancels the rpc.
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	rpc.HandleStreamClose(context.Canceled)
+	if _, err := rpc.ReadOne(); err != context.Canceled {
+		t.Fatalf("expected canceled got %v", err)
+	}
+
+	// remote errors are not transport errors.
+	rpc = NewClientRPC(ctx, "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := rpc.HandlePacket(NewCallDataPacket(nil, false, true, errors.New("remote failed"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	rpc.HandleStreamClose(nil)
+	if _, err := rpc.ReadOne(); err == nil || IsTransportError(err) {
+		t.Fatalf("expected remote error got %v", err)
+	}
+}
+
+func TestClientRPC_CloseBeforeStart(t *testing.T) {
+	// the read pump can close the stream before Start sets the writer.
+	readErr := errors.New("connection reset")
+	for _, closeErr := range []error{nil, readErr} {
+		rpc := NewClientRPC(context.Background(), "test.Service", "Method")
+		rpc.HandleStreamClose(closeErr)
+		if _, err := rpc.ReadOne(); !IsTransportError(err) {
+			t.Fatalf("expected transport error got %v", err)
+		}
+		rpc.Close()
+	}
+}
+
+func TestClientRPC_UnexpectedCallStart(t *testing.T) {
+	ctx := context.Background()
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	err := rpc.HandlePacket(NewCallStartPacket("test.Service", "Method", nil, false))
+	if !errors.Is(err, ErrUnrecognizedPacket) {
+		t.Fatalf("expected unrecognized packet got %v", err)
+	}
+	// the read pump closes the stream after the handler returns an error.
+	rpc.HandleStreamClose(err)
+	_, err = rpc.ReadOne()
+	if !errors.Is(err, ErrUnrecognizedPacket) || IsTransportError(err) {
+		t.Fatalf("expected unrecognized packet got %v", err)
+	}
+}
+
+func TestClientRPC_PendingRecv(t *testing.T) {
+	rpc := NewClientRPC(context.Background(), "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, msg := range []string{"a", "b"} {
+		if err := rpc.HandlePacket(interopCallData(msg)); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if n := rpc.PendingRecv(); n != 2 {
+		t.Fatalf("expected 2 pending got %d", n)
+	}
+	if _, err := rpc.ReadOne(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if n := rpc.PendingRecv(); n != 1 {
+		t.Fatalf("expected 1 pending got %d", n)
+	}
+}
+
+func TestClientRPC_MaxResponseSize(t *testing.T) {
+	ctx := WithMaxResponseSize(context.Background(), 4)
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := rpc.HandlePacket(NewCallDataPacket([]byte("abcd"), false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	err := rpc.HandlePacket(NewCallDataPacket([]byte("abcde"), false, false, nil))
+	if err != ErrMessageTooLarge {
+		t.Fatalf("expected message too large got %v", err)
+	}
+	if data, err := rpc.ReadOne(); err != nil || string(data) != "abcd" {
+		t.Fatalf("unexpected read: %q, %v", data, err)
+	}
+	if _, err := rpc.ReadOne(); err != ErrMessageTooLarge {
+		t.Fatalf("expected message too large got %v", err)
+	}
+}
+
+func TestClientRPC_RecvStallTimeout(t *testing.T) {
+	ctx := WithRecvStallTimeout(context.Background(), 10*time.Millisecond)
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	rpc.SetRecvBufferSize(1)
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := rpc.HandlePacket(NewCallDataPacket([]byte("a"), false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	// the buffer is full and the message is not read.
+	err := rpc.HandlePacket(NewCallDataPacket([]byte("b"), false, false, nil))
+	if err != ErrRecvStalled {
+		t.Fatalf("expected recv stalled got %v", err)
+	}
+	if data, err := rpc.ReadOne(); err != nil || string(data) != "a" {
+		t.Fatalf("unexpected read: %q, %v", data, err)
+	}
+	if _, err := rpc.ReadOne(); err != ErrRecvStalled {
+		t.Fatalf("expected recv stalled got %v", err)
+	}
+}
+
+func TestClientRPC_ReadAfterClose(t *testing.T) {
+	// messages received before the rpc was closed locally are not lost.
+	for i := 0; i < 20; i++ {
+		rpc := NewClientRPC(context.Background(), "test.Service", "Method")
+		if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+			t.Fatal(err.Error())
+		}
+		if err := rpc.HandlePacket(NewCallDataPacket([]byte("a"), false, true, nil)); err != nil {
+			t.Fatal(err.Error())
+		}
+		rpc.Close()
+		if data, err := rpc.ReadOne(); err != nil || string(data) != "a" {
+			t.Fatalf("expected a got %q: %v", data, err)
+		}
+		if _, err := rpc.ReadOne(); err != io.EOF {
+			t.Fatalf("expected %v got %v", io.EOF, err)
+		}
+	}
+}
@@ -2,6 +2,7 @@ package srpc
 
 import (
 	"context"
+	"io"
 
 	"github.com/pkg/errors"
 )
@@ -26,23 +27,58 @@ type OpenStreamFunc = func(
 
 // client implements Client with a transport.
 type client struct {
+	// clientOptions contains the client options.
+	clientOptions
 	// openStream opens a new stream.
 	openStream OpenStreamFunc
 }
 
+// clientOptions contains the options shared by the Client implementations.
+type clientOptions struct {
+	// lenientUnary ignores extra responses to unary rpcs.
+	lenientUnary bool
+}
+
+// ClientOption configures a Client.
+type ClientOption func(o *clientOptions)
+
+// applyClientOptions applies the options to o.
+func applyClientOptions(o *clientOptions, opts []ClientOption) {
+	for _, opt := range opts {
+		opt(o)
+	}
+}
+
+// WithLenientUnary ignores any extra responses the server sends for a unary
+// rpc instead of returning ErrUnexpectedMessage.
+//
+// Useful with peers which do not strictly follow the unary contract.
+func WithLenientUnary() ClientOption {
+	return func(o *clientOptions) {
+		o.lenientUnary = true
+	}
+}
+
 // NewClient constructs a client with a OpenStreamFunc.
-func NewClient(openStream OpenStreamFunc) Client {
-	return &client{
+func NewClient(openStream OpenStreamFunc, opts ...ClientOption) Client {
+	c := &client{
 		openStream: openStream,
 	}
+	applyClientOptions(&c.clientOptions, opts)
+	return c
 }
 
 // Invoke executes a unary RPC with the remote.
-func (c *client) Invoke(rctx context.Context, service, method string, in, out Message) error {
+func (c *client) Invoke(rctx context.Context, service, method string, in, out Message) (err error) {
+	rctx, endTrace := startTraceTask(rctx, TraceTaskClientCall, service, method)
+	defer func() {
+		endTrace(err)
+	}()
+
 	ctx, ctxCancel := context.WithCancel(rctx)
 	defer ctxCancel()
 
-	firstMsg, err := in.MarshalVT()
+	firstMsg, err := marshalMessage(in)
 	if err != nil {
 		return err
 	}
@@ -56,9 +92,23 @@ func (c *client) Invoke(rctx context.Context, service, method string, in, out Me
 	}
 	msg, err := clientRPC.ReadOne()
 	if err != nil {
+		if err == context.Canceled {
+			// notify the server the call was canceled & release the stream.
+			_ = writer.WritePacket(NewCallDataPacket(nil, false, true, context.Canceled))
+			clientRPC.Close()
+		}
 		// this includes any server returned error.
 		return err
 	}
+	// wait for the server to complete the call: it may return an error.
+	if err := readUnaryComplete(clientRPC.ReadOne, c.lenientUnary); err != nil {
+		if err == ErrUnexpectedMessage {
+			// the server sent more than one response: cancel the call.
+			_ = writer.WritePacket(NewCallDataPacket(nil, false, true, context.Canceled))
+			clientRPC.Close()
+		}
+		return err
+	}
 	if err := out.UnmarshalVT(msg); err != nil {
 		return errors.Wrap(ErrInvalidMessage, err.Error())
 	}
@@ -66,13 +116,40 @@ func (c *client) Invoke(rctx context.Context, service, method string, in, out Me
 	return nil
 }
 
+// readUnaryComplete waits for the remote to complete a unary rpc after the
+// response was received.
+//
+// The response is valid if the stream was canceled or closed without
+// completing. Returns ErrUnexpectedMessage if the remote sent another message,
+// unless lenient is set, or the error returned by the remote.
+func readUnaryComplete(readOne func() ([]byte, error), lenient bool) error {
+	_, err := readOne()
+	switch {
+	case err == nil:
+		if !lenient {
+			return ErrUnexpectedMessage
+		}
+		return nil
+	case err == io.EOF, err == context.Canceled, IsTransportError(err):
+		return nil
+	default:
+		return err
+	}
+}
+
 // NewStream starts a streaming RPC with the remote & returns the stream.
 // firstMsg is optional.
-func (c *client) NewStream(ctx context.Context, service, method string, firstMsg Message) (Stream, error) {
+//
+// The trace task covers opening the stream, the handler is traced by the server.
+func (c *client) NewStream(ctx context.Context, service, method string, firstMsg Message) (strm Stream, err error) {
+	ctx, endTrace := startTraceTask(ctx, TraceTaskClientCall, service, method)
+	defer func() {
+		endTrace(err)
+	}()
+
 	var firstMsgData []byte
 	if firstMsg != nil {
-		var err error
-		firstMsgData, err = firstMsg.MarshalVT()
+		firstMsgData, err = marshalMessage(firstMsg)
 		if err != nil {
 			return nil, err
 		}
@@ -87,7 +164,35 @@ func (c *client) NewStream(ctx context.Context, service, method string, firstMsg
 		return nil, err
 	}
 
-	return NewMsgStream(ctx, clientRPC.writer, clientRPC.dataCh), nil
+	return NewMsgStream(ctx, clientRPC.writer, clientRPC), nil
+}
+
+// InvokeRaw executes a unary RPC with the remote with an encoded request.
+//
+// Returns the encoded response. Useful for dynamic dispatch, for example in
+// gateways which do not have the generated message types.
+func InvokeRaw(ctx context.Context, c Client, service, method string, in []byte) ([]byte, error) {
+	inMsg, out := RawMessage(in), new(RawMessage)
+	if err := c.Invoke(ctx, service, method, &inMsg, out); err != nil {
+		return nil, err
+	}
+	return *out, nil
+}
+
+// NewServerStream starts a server-streaming RPC with the request.
+//
+// Sends the request with CallStart and closes the send side. Call MsgRecv on
+// the returned Stream until it returns io.EOF to read the responses.
+func NewServerStream(ctx context.Context, c Client, service, method string, in Message) (Stream, error) {
+	strm, err := c.NewStream(ctx, service, method, in)
+	if err != nil {
+		return nil, err
+	}
+	if err := strm.CloseSend(); err != nil {
+		_ = strm.Close()
+		return nil, err
+	}
+	return strm, nil
 }
 
 // _ is a type assertion
@@ -0,0 +1,161 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"net"
+	"testing"
+	"time"
+)
+
+func TestClient_InvokeCancel(t *testing.T) {
+	// the handler reads the request, then waits for the next message and
+	// reports the error.
+	started, errCh := make(chan struct{}), make(chan error, 1)
+	handler := newTestHandler(func(strm Stream) error {
+		var msg RawMessage
+		if err := strm.MsgRecv(&msg); err != nil {
+			return err
+		}
+		close(started)
+		err := strm.MsgRecv(&msg)
+		errCh <- err
+		return err
+	})
+	mux := NewMux()
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := NewServer(mux)
+
+	srvPipe, clientPipe := net.Pipe()
+	go func() {
+		_ = server.HandleStream(context.Background(), srvPipe)
+	}()
+	client := NewClient(NewSingleStreamOpener(clientPipe).GetOpenStreamFunc())
+
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	go func() {
+		<-started
+		ctxCancel()
+	}()
+	in, out := RawMessage("hello"), new(RawMessage)
+	if err := client.Invoke(ctx, "test.Service", "Method", &in, out); err != context.Canceled {
+		t.Fatalf("expected %v got %v", context.Canceled, err)
+	}
+
+	select {
+	case err := <-errCh:
+		if err == nil || err.Error() != context.Canceled.Error() {
+			t.Fatalf("expected the server to observe %v got %v", context.Canceled, err)
+		}
+	case <-time.After(time.Second):
+		t.Fatal("expected the server to observe the cancel")
+	}
+}
+
+func TestClient_InvokeRemoteError(t *testing.T) {
+	handlerErr := errors.New("handler failed")
+	// the second handler sends a response, then returns the error.
+	respondErrHandler := newTestHandler(func(strm Stream) error {
+		var msg RawMessage
+		if err := strm.MsgRecv(&msg); err != nil {
+			return err
+		}
+		if err := strm.MsgSend(&msg); err != nil {
+			return err
+		}
+		return handlerErr
+	})
+	for i, handler := range []Handler{newErrHandler(handlerErr), respondErrHandler} {
+		mux := NewMux()
+		if err := mux.Register(handler); err != nil {
+			t.Fatal(err.Error())
+		}
+		client := NewClient(NewServerPipe(NewServer(mux)))
+		in, out := RawMessage("hello"), new(RawMessage)
+		err := client.Invoke(context.Background(), "test.Service", "Method", &in, out)
+		if err == nil || err.Error() != handlerErr.Error() || IsTransportError(err) {
+			t.Fatalf("handler %d: expected remote error %v got %v", i, handlerErr, err)
+		}
+	}
+}
+
+func TestClient_InvokeUnexpectedMessage(t *testing.T) {
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := NewServer(mux)
+	in, out := RawMessage("hello"), new(RawMessage)
+
+	// the ServerStream method sends three responses.
+	client := NewClient(NewServerPipe(server))
+	err := client.Invoke(context.Background(), interopService, "ServerStream", &in, out)
+	if err != ErrUnexpectedMessage {
+		t.Fatalf("expected %v got %v", ErrUnexpectedMessage, err)
+	}
+
+	client = NewClient(NewServerPipe(server), WithLenientUnary())
+	if err := client.Invoke(context.Background(), interopService, "ServerStream", &in, out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(*out) != "hello" {
+		t.Fatalf("expected hello got %q", string(*out))
+	}
+}
+
+func TestClient_InvokeEmptyResponse(t *testing.T) {
+	// the handler responds with an empty message and completes in one packet.
+	handler := newTestHandler(func(strm Stream) error {
+		var msg RawMessage
+		if err := strm.MsgRecv(&msg); err != nil {
+			return err
+		}
+		return MsgSendComplete(strm, &RawMessage{})
+	})
+	mux := NewMux()
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	clients := []Client{NewClient(NewServerPipe(NewServer(mux))), NewDirectClient(mux)}
+	for _, client := range clients {
+		in, out := RawMessage{}, RawMessage("stale")
+		if err := client.Invoke(context.Background(), "test.Service", "Method", &in, &out); err != nil {
+			t.Fatalf("%T: %v", client, err)
+		}
+		if len(out) != 0 {
+			t.Fatalf("%T: expected an empty response got %q", client, string(out))
+		}
+	}
+}
+
+// respondCancelWriter responds to the CallStart, then closes the stream with
+// context.Canceled before the rpc completes.
+type respondCancelWriter struct {
+	discardWriter
+	msgHandler   PacketHandler
+	closeHandler CloseHandler
+}
+
+// WritePacket writes a packet to the remote.
+func (w *respondCancelWriter) WritePacket(p *Packet) error {
+	if p.GetCallStart() != nil {
+		_ = w.msgHandler(NewCallDataPacket([]byte("hello"), false, false, nil))
+		w.closeHandler(context.Canceled)
+	}
+	return nil
+}
+
+func TestClient_InvokeCanceledAfterResponse(t *testing.T) {
+	client := NewClient(func(ctx context.Context, msgHandler PacketHandler, closeHandler CloseHandler) (Writer, error) {
+		return &respondCancelWriter{msgHandler: msgHandler, closeHandler: closeHandler}, nil
+	})
+	in, out := RawMessage("hello"), new(RawMessage)
+	if err := client.Invoke(context.Background(), "test.Service", "Method", &in, out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(*out) != "hello" {
+		t.Fatalf("expected hello got %q", string(*out))
+	}
+}
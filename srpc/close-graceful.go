@@ -0,0 +1,42 @@
+package srpc
+
+import (
+	"context"
+	"time"
+)
+
+// CloseGraceful gracefully closes a stream.
+//
+// Closes the send side, then waits for the remote to complete the rpc before
+// closing the stream, so that the final messages sent by the remote are not
+// truncated. Messages received before the remote completed can still be read
+// with MsgRecv after CloseGraceful returns.
+//
+// If the stream does not support StreamRecvClosed, waits for the stream
+// context or the timeout.
+//
+// If the remote does not complete within the timeout, closes the stream and
+// returns context.DeadlineExceeded. If timeout <= 0, waits for the remote or
+// the stream context without a timeout.
+func CloseGraceful(strm Stream, timeout time.Duration) error {
+	err := strm.CloseSend()
+	if err == nil {
+		var timeoutCh <-chan time.Time
+		if timeout > 0 {
+			timer := time.NewTimer(timeout)
+			defer timer.Stop()
+			timeoutCh = timer.C
+		}
+		select {
+		case <-StreamRecvClosed(strm):
+		case <-strm.Context().Done():
+			err = context.Canceled
+		case <-timeoutCh:
+			err = context.DeadlineExceeded
+		}
+	}
+	if closeErr := strm.Close(); err == nil {
+		err = closeErr
+	}
+	return err
+}
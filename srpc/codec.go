@@ -0,0 +1,83 @@
+package srpc
+
+import (
+	"encoding/json"
+
+	"github.com/pkg/errors"
+)
+
+// Codec encodes and decodes message payloads.
+//
+// The payload is carried as the data of the CallData packets: the transport
+// framing is unchanged. Both sides of the rpc must use the same Codec.
+type Codec interface {
+	// Marshal encodes the value.
+	Marshal(v interface{}) ([]byte, error)
+	// Unmarshal decodes the data into the value.
+	Unmarshal(data []byte, v interface{}) error
+}
+
+// MessageCodec is the default Codec which encodes Message values.
+type MessageCodec struct{}
+
+// Marshal encodes the value.
+func (MessageCodec) Marshal(v interface{}) ([]byte, error) {
+	msg, ok := v.(Message)
+	if !ok {
+		return nil, errors.Errorf("message codec: %T does not implement Message", v)
+	}
+	return msg.MarshalVT()
+}
+
+// Unmarshal decodes the data into the value.
+func (MessageCodec) Unmarshal(data []byte, v interface{}) error {
+	msg, ok := v.(Message)
+	if !ok {
+		return errors.Errorf("message codec: %T does not implement Message", v)
+	}
+	return msg.UnmarshalVT(data)
+}
+
+// JSONCodec is a Codec which encodes values with encoding/json.
+type JSONCodec struct{}
+
+// Marshal encodes the value.
+func (JSONCodec) Marshal(v interface{}) ([]byte, error) {
+	return json.Marshal(v)
+}
+
+// Unmarshal decodes the data into the value.
+func (JSONCodec) Unmarshal(data []byte, v interface{}) error {
+	return json.Unmarshal(data, v)
+}
+
+// MsgSendWith encodes the value with the codec and sends it to the remote.
+func MsgSendWith(strm Stream, codec Codec, v interface{}) error {
+	data, err := codec.Marshal(v)
+	if err != nil {
+		return err
+	}
+	msg := RawMessage(data)
+	return strm.MsgSend(&msg)
+}
+
+// MsgRecvWith receives a message from the remote and decodes it with the codec
+// into the value.
+//
+// Returns ErrInvalidMessage if the message failed to decode.
+func MsgRecvWith(strm Stream, codec Codec, v interface{}) error {
+	var msg RawMessage
+	if err := strm.MsgRecv(&msg); err != nil {
+		return err
+	}
+	if err := codec.Unmarshal(msg, v); err != nil {
+		return errors.Wrap(ErrInvalidMessage, err.Error())
+	}
+	return nil
+}
+
+// _ is a type assertion
+var (
+	_ Codec = MessageCodec{}
+	_ Codec = JSONCodec{}
+)
@@ -0,0 +1,43 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+
+	"github.com/pkg/errors"
+)
+
+// codecTestMsg is a message encoded with JSONCodec in tests.
+type codecTestMsg struct {
+	Body  string `json:"body"`
+	Count int    `json:"count"`
+}
+
+func TestCodec(t *testing.T) {
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+	s1, s2 := NewPipeStream(ctx)
+
+	in := &codecTestMsg{Body: "hello", Count: 3}
+	if err := MsgSendWith(s1, JSONCodec{}, in); err != nil {
+		t.Fatal(err.Error())
+	}
+	out := &codecTestMsg{}
+	if err := MsgRecvWith(s2, JSONCodec{}, out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if *out != *in {
+		t.Fatalf("expected %v got %v", in, out)
+	}
+
+	raw := RawMessage("hello")
+	if err := MsgSendWith(s1, MessageCodec{}, &raw); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := MsgRecvWith(s2, JSONCodec{}, out); !errors.Is(err, ErrInvalidMessage) {
+		t.Fatalf("expected %v got %v", ErrInvalidMessage, err)
+	}
+	if err := MsgSendWith(s1, MessageCodec{}, in); err == nil {
+		t.Fatal("expected an error encoding a non-Message value")
+	}
+}
@@ -0,0 +1,36 @@
+package srpc
+
+import (
+	"context"
+	"net"
+)
+
+// ConnInfo contains information about the connection carrying a rpc.
+type ConnInfo struct {
+	// LocalAddr is the local address, if known.
+	LocalAddr net.Addr
+	// RemoteAddr is the remote peer address, if known.
+	RemoteAddr net.Addr
+}
+
+// connInfoCtxKey is the context key for ConnInfo.
+type connInfoCtxKey struct{}
+
+// WithConnInfo attaches the ConnInfo to the context.
+func WithConnInfo(ctx context.Context, info *ConnInfo) context.Context {
+	return context.WithValue(ctx, connInfoCtxKey{}, info)
+}
+
+// GetConnInfo returns the ConnInfo attached to the context, if any.
+//
+// The Server attaches the ConnInfo to the rpc context for connections accepted
+// with AcceptMuxedListener and for streams which implement net.Conn.
+func GetConnInfo(ctx context.Context) (*ConnInfo, bool) {
+	info, ok := ctx.Value(connInfoCtxKey{}).(*ConnInfo)
+	return info, ok && info != nil
+}
+
+// newConnInfo constructs the ConnInfo for a net.Conn.
+func newConnInfo(nc net.Conn) *ConnInfo {
+	return &ConnInfo{LocalAddr: nc.LocalAddr(), RemoteAddr: nc.RemoteAddr()}
+}
@@ -0,0 +1,23 @@
+package srpc
+
+import "context"
+
+// contextNameCtxKey is the context key for the context name.
+type contextNameCtxKey struct{}
+
+// WithContextName attaches a name to the context for diagnostics.
+//
+// The name is appended to the name of the parent context, if any, separated
+// with a slash: for example conn/rpc/nested.
+func WithContextName(ctx context.Context, name string) context.Context {
+	if parent := GetContextName(ctx); parent != "" {
+		name = parent + "/" + name
+	}
+	return context.WithValue(ctx, contextNameCtxKey{}, name)
+}
+
+// GetContextName returns the name attached to the context, if any.
+func GetContextName(ctx context.Context) string {
+	name, _ := ctx.Value(contextNameCtxKey{}).(string)
+	return name
+}
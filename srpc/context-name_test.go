@@ -0,0 +1,23 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+)
+
+func TestContextName(t *testing.T) {
+	ctx := context.Background()
+	if name := GetContextName(ctx); name != "" {
+		t.Fatalf("expected no name but got %q", name)
+	}
+	ctx = WithContextName(ctx, "conn")
+	childCtx, childCtxCancel := context.WithCancel(WithContextName(ctx, "rpc"))
+	defer childCtxCancel()
+	nestedCtx := WithContextName(childCtx, "nested")
+	if name := GetContextName(nestedCtx); name != "conn/rpc/nested" {
+		t.Fatalf("unexpected name: %q", name)
+	}
+	if name := GetContextName(ctx); name != "conn" {
+		t.Fatalf("unexpected name: %q", name)
+	}
+}
@@ -0,0 +1,142 @@
+package srpc
+
+import (
+	"context"
+	"io"
+
+	"github.com/pkg/errors"
+)
+
+// directClient implements Client by invoking an Invoker in-process.
+type directClient struct {
+	// clientOptions contains the client options.
+	clientOptions
+	// invoker handles the rpcs
+	invoker Invoker
+}
+
+// NewDirectClient constructs a Client which calls the Invoker in-process.
+//
+// Each rpc runs the handler in a separate goroutine connected with an
+// in-memory stream. The messages are encoded as with a transport, but the
+// packet framing is skipped. The stream buffers a few messages before
+// blocking the sender, like the transport. Useful for in-process calls to
+// services which may later be moved to a separate process.
+func NewDirectClient(invoker Invoker, opts ...ClientOption) Client {
+	c := &directClient{invoker: invoker}
+	applyClientOptions(&c.clientOptions, opts)
+	return c
+}
+
+// Invoke executes a unary RPC with the remote.
+func (c *directClient) Invoke(ctx context.Context, service, method string, in, out Message) error {
+	strm, err := c.NewStream(ctx, service, method, in)
+	if err != nil {
+		return err
+	}
+	defer strm.Close()
+	if err := strm.CloseSend(); err != nil {
+		return err
+	}
+	readOne := func() ([]byte, error) {
+		var msg RawMessage
+		if err := strm.MsgRecv(&msg); err != nil {
+			return nil, err
+		}
+		return msg, nil
+	}
+	msg, err := readOne()
+	if err != nil {
+		return err
+	}
+	// wait for the handler to return: it may return an error.
+	if err := readUnaryComplete(readOne, c.lenientUnary); err != nil {
+		return err
+	}
+	if err := out.UnmarshalVT(msg); err != nil {
+		return errors.Wrap(ErrInvalidMessage, err.Error())
+	}
+	return nil
+}
+
+// NewStream starts a streaming RPC with the remote & returns the stream.
+// firstMsg is optional.
+func (c *directClient) NewStream(ctx context.Context, service, method string, firstMsg Message) (Stream, error) {
+	clientStrm, serverStrm := NewPipeStream(ctx)
+	strm := &directStream{Stream: clientStrm, server: serverStrm, done: make(chan struct{})}
+	if firstMsg != nil {
+		if err := clientStrm.MsgSend(firstMsg); err != nil {
+			_ = strm.Close()
+			return nil, err
+		}
+	}
+	go func() {
+		ok, err := c.invoker.InvokeMethod(service, method, serverStrm)
+		if err == nil && !ok {
+			err = ErrUnimplemented
+		}
+		strm.err = err
+		close(strm.done)
+		_ = serverStrm.Close()
+	}()
+	return strm, nil
+}
+
+// directStream is the client side of a rpc started by directClient.
+type directStream struct {
+	// Stream is the client end of the pipe
+	Stream
+	// server is the server end of the pipe
+	server Stream
+	// done is closed when the handler returned
+	done chan struct{}
+	// err is the error returned by the handler
+	// immutable after done is closed
+	err error
+}
+
+// MsgRecv receives an incoming message from the remote.
+// Parses the message into the object at msg.
+//
+// Returns the handler error, if any, after the handler returned.
+func (s *directStream) MsgRecv(msg Message) error {
+	return s.MsgRecvContext(s.Context(), msg)
+}
+
+// MsgRecvContext receives an incoming message from the remote.
+// ctx must be derived from the stream Context.
+//
+// Returns the handler error, if any, after the handler returned.
+func (s *directStream) MsgRecvContext(ctx context.Context, msg Message) error {
+	err := MsgRecvContext(ctx, s.Stream, msg)
+	if err != io.EOF {
+		return err
+	}
+	select {
+	case <-s.done:
+		if s.err != nil {
+			return s.err
+		}
+		return io.EOF
+	case <-ctx.Done():
+		return context.Canceled
+	}
+}
+
+// Close closes the stream and cancels the handler Context.
+func (s *directStream) Close() error {
+	err := s.Stream.Close()
+	_ = s.server.Close()
+	return err
+}
+
+// Unwrap returns the underlying Stream.
+func (s *directStream) Unwrap() Stream {
+	return s.Stream
+}
+
+// _ is a type assertion
+var (
+	_ Client = ((*directClient)(nil))
+	_ Stream = ((*directStream)(nil))
+)
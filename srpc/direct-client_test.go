@@ -0,0 +1,99 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"io"
+	"testing"
+)
+
+func TestDirectClient(t *testing.T) {
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	handlerErr := errors.New("handler failed")
+	if err := mux.Register(newErrHandler(handlerErr)); err != nil {
+		t.Fatal(err.Error())
+	}
+	client := NewDirectClient(mux)
+
+	in, out := RawMessage("hello"), RawMessage(nil)
+	if err := client.Invoke(ctx, interopService, "Unary", &in, &out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != "hello" {
+		t.Fatalf("unexpected response: %q", out)
+	}
+
+	if err := client.Invoke(ctx, "test.Service", "Method", &in, &out); err != handlerErr {
+		t.Fatalf("expected handler error but got: %v", err)
+	}
+	if err := client.Invoke(ctx, "test.Service", "Other", &in, &out); err != ErrUnimplemented {
+		t.Fatalf("expected unimplemented but got: %v", err)
+	}
+
+	strm, err := client.NewStream(ctx, interopService, "ClientStream", nil)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer strm.Close()
+	for i := 0; i < 10; i++ {
+		msg := RawMessage("x")
+		if err := strm.MsgSend(&msg); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if err := strm.CloseSend(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := strm.MsgRecv(&out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(out) != 1 || out[0] != 10 {
+		t.Fatalf("unexpected count: %v", out)
+	}
+	if err := strm.MsgRecv(&out); err != io.EOF {
+		t.Fatalf("expected eof but got: %v", err)
+	}
+}
+
+func TestDirectClient_Close(t *testing.T) {
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	in, out := RawMessage("hello"), RawMessage(nil)
+
+	// the ServerStream method sends three responses.
+	client := NewDirectClient(mux)
+	if err := client.Invoke(ctx, interopService, "ServerStream", &in, &out); err != ErrUnexpectedMessage {
+		t.Fatalf("expected %v got %v", ErrUnexpectedMessage, err)
+	}
+	client = NewDirectClient(mux, WithLenientUnary())
+	if err := client.Invoke(ctx, interopService, "ServerStream", &in, &out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != "hello" {
+		t.Fatalf("unexpected response: %q", out)
+	}
+
+	// closing the stream while the handler sends must not panic.
+	strm, err := client.NewStream(ctx, interopService, "BidiStream", nil)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	for i := 0; i < 10; i++ {
+		if err := strm.MsgSend(&in); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if err := strm.Close(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := strm.MsgSend(&in); err == nil {
+		t.Fatal("expected send after close to fail")
+	}
+}
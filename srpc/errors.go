@@ -1,6 +1,9 @@
 package srpc
 
-import "errors"
+import (
+	"context"
+	"errors"
+)
 
 var (
 	// ErrUnimplemented is returned if the RPC method was not implemented.
@@ -17,4 +20,60 @@ var (
 	ErrEmptyMethodID = errors.New("method id empty")
 	// ErrEmptyServiceID is returned if the service id was empty.
 	ErrEmptyServiceID = errors.New("service id empty")
+	// ErrStreamBusy is returned if a non-multiplexed transport is already in use.
+	ErrStreamBusy = errors.New("transport is busy with another rpc: use a stream multiplexer for concurrent calls")
+	// ErrFaultInjected is returned by FaultyReadWriteCloser after the transport was cut.
+	ErrFaultInjected = errors.New("injected transport fault")
+	// ErrInvalidChunk is returned if a chunk is malformed or out of order.
+	ErrInvalidChunk = errors.New("invalid message chunk")
+	// ErrMethodTimeout is returned if the handler exceeded the method timeout.
+	ErrMethodTimeout = errors.New("rpc method timed out")
+	// ErrMessageTooLarge is returned if a message exceeds the max message size.
+	ErrMessageTooLarge = errors.New("message exceeds the max message size")
+	// ErrTooManyMessages is returned if more than the max number of messages were received.
+	ErrTooManyMessages = errors.New("too many messages received")
+	// ErrRecvLimitExceeded is returned if the client sent more than the server recv limit.
+	ErrRecvLimitExceeded = errors.New("rpc recv limit exceeded")
+	// ErrRateLimited is returned if the method call rate limit was exceeded.
+	ErrRateLimited = errors.New("rpc rate limited")
+	// ErrRecvStalled is returned if the incoming message buffer stayed full for too long.
+	ErrRecvStalled = errors.New("rpc receive stalled: messages were not read in time")
+	// ErrUnexpectedMessage is returned if the server sent more than one response to a unary rpc.
+	ErrUnexpectedMessage = errors.New("unexpected message: unary rpc received more than one response")
+	// ErrStreamIdle is returned if no message was received within the recv timeout.
+	ErrStreamIdle = errors.New("stream idle: no message received within the timeout")
 )
+
+// remoteErrors are the well-known errors recognized by ErrorFromString.
+var remoteErrors = []error{
+	ErrUnimplemented,
+	ErrCompleted,
+	ErrUnrecognizedPacket,
+	ErrEmptyPacket,
+	ErrInvalidMessage,
+	ErrEmptyMethodID,
+	ErrEmptyServiceID,
+	ErrMethodTimeout,
+	ErrMessageTooLarge,
+	ErrTooManyMessages,
+	ErrRecvLimitExceeded,
+	ErrRateLimited,
+	context.Canceled,
+	context.DeadlineExceeded,
+}
+
+// ErrorFromString converts an error string received from the remote to an error.
+//
+// Returns the matching error variable if the string is a well-known error, so
+// that errors.Is works across the connection. Returns nil if errStr is empty.
+func ErrorFromString(errStr string) error {
+	if errStr == "" {
+		return nil
+	}
+	for _, err := range remoteErrors {
+		if errStr == err.Error() {
+			return err
+		}
+	}
+	return errors.New(errStr)
+}
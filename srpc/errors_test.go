@@ -0,0 +1,21 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+)
+
+func TestErrorFromString(t *testing.T) {
+	for _, err := range remoteErrors {
+		if got := ErrorFromString(err.Error()); got != err {
+			t.Fatalf("expected %v but got %v", err, got)
+		}
+	}
+	if err := ErrorFromString(""); err != nil {
+		t.Fatalf("expected nil but got %v", err)
+	}
+	err := ErrorFromString("some other error")
+	if err == nil || err.Error() != "some other error" || err == context.Canceled {
+		t.Fatalf("unexpected error: %v", err)
+	}
+}
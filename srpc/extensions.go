@@ -0,0 +1,67 @@
+package srpc
+
+import (
+	"context"
+	"reflect"
+	"sync"
+)
+
+// Extensions is a mutable set of values attached to a single rpc, keyed by type.
+//
+// Use to pass state between interceptors and the handler, for example an auth
+// wrapper attaching the parsed identity of the caller. Concurrency safe.
+type Extensions struct {
+	// mtx guards vals
+	mtx sync.Mutex
+	// vals contains the values keyed by type
+	vals map[reflect.Type]interface{}
+}
+
+// NewExtensions constructs a new empty Extensions.
+func NewExtensions() *Extensions {
+	return &Extensions{vals: make(map[reflect.Type]interface{})}
+}
+
+// GetExtension returns the value of type T, if set.
+func GetExtension[T any](ext *Extensions) (T, bool) {
+	ext.mtx.Lock()
+	defer ext.mtx.Unlock()
+	val, ok := ext.vals[extensionKey[T]()].(T)
+	return val, ok
+}
+
+// SetExtension sets the value of type T, replacing any existing value.
+func SetExtension[T any](ext *Extensions, val T) {
+	ext.mtx.Lock()
+	ext.vals[extensionKey[T]()] = val
+	ext.mtx.Unlock()
+}
+
+// DeleteExtension removes the value of type T, if set.
+func DeleteExtension[T any](ext *Extensions) {
+	ext.mtx.Lock()
+	delete(ext.vals, extensionKey[T]())
+	ext.mtx.Unlock()
+}
+
+// extensionKey returns the key for values of type T.
+func extensionKey[T any]() reflect.Type {
+	return reflect.TypeOf((*T)(nil)).Elem()
+}
+
+// extensionsCtxKey is the context key for Extensions.
+type extensionsCtxKey struct{}
+
+// WithExtensions attaches the Extensions to the context.
+func WithExtensions(ctx context.Context, ext *Extensions) context.Context {
+	return context.WithValue(ctx, extensionsCtxKey{}, ext)
+}
+
+// GetExtensions returns the Extensions attached to the context, if any.
+//
+// Each ServerRPC attaches its own Extensions to the rpc context: use
+// GetExtensions(strm.Context()) in handlers and Mux wrappers.
+func GetExtensions(ctx context.Context) (*Extensions, bool) {
+	ext, ok := ctx.Value(extensionsCtxKey{}).(*Extensions)
+	return ext, ok && ext != nil
+}
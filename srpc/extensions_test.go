@@ -0,0 +1,39 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+)
+
+// testIdentity is an extension value used in tests.
+type testIdentity struct {
+	name string
+}
+
+func TestExtensions(t *testing.T) {
+	ext := NewExtensions()
+	if _, ok := GetExtension[*testIdentity](ext); ok {
+		t.Fatal("expected no value")
+	}
+	SetExtension(ext, &testIdentity{name: "alice"})
+	SetExtension(ext, "other")
+	id, ok := GetExtension[*testIdentity](ext)
+	if !ok || id.name != "alice" {
+		t.Fatalf("expected identity got %v", id)
+	}
+	DeleteExtension[*testIdentity](ext)
+	if _, ok := GetExtension[*testIdentity](ext); ok {
+		t.Fatal("expected the value to be deleted")
+	}
+	if val, ok := GetExtension[string](ext); !ok || val != "other" {
+		t.Fatalf("expected other got %q", val)
+	}
+
+	// each rpc has its own extensions, even if the parent context has some.
+	parentCtx := WithExtensions(context.Background(), ext)
+	rpc := NewServerRPC(parentCtx, NewMux())
+	rpcExt, ok := GetExtensions(rpc.Context())
+	if !ok || rpcExt != rpc.Extensions() || rpcExt == ext {
+		t.Fatal("expected the rpc extensions to be attached to the rpc context")
+	}
+}
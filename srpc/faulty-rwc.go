@@ -0,0 +1,153 @@
+package srpc
+
+import (
+	"io"
+	"sync"
+	"time"
+)
+
+// FaultPlan configures the faults injected by a FaultyReadWriteCloser.
+//
+// Zero values disable the corresponding fault.
+type FaultPlan struct {
+	// CutAfterBytes cuts the transport after this many bytes were transferred.
+	// Counts both the read and written bytes.
+	CutAfterBytes int
+	// CutAfterDuration cuts the transport after the duration elapses.
+	CutAfterDuration time.Duration
+	// CorruptRead enables corrupting the read byte at CorruptReadOffset.
+	CorruptRead bool
+	// CorruptReadOffset is the offset of the read byte to corrupt.
+	CorruptReadOffset int
+}
+
+// FaultyReadWriteCloser wraps a transport and injects faults per a FaultPlan.
+// Intended for testing retry and reconnect logic.
+//
+// Once cut, the underlying transport is closed, and Read and Write return
+// ErrFaultInjected.
+type FaultyReadWriteCloser struct {
+	// rwc is the underlying transport
+	rwc io.ReadWriteCloser
+	// plan is the fault plan
+	plan FaultPlan
+	// timer is the CutAfterDuration timer, if any
+	timer *time.Timer
+
+	// mtx guards below fields
+	mtx sync.Mutex
+	// transferred is the number of bytes read and written
+	transferred int
+	// readOffset is the number of bytes read
+	readOffset int
+	// cut indicates the transport was cut
+	cut bool
+}
+
+// NewFaultyReadWriteCloser constructs a new FaultyReadWriteCloser.
+func NewFaultyReadWriteCloser(rwc io.ReadWriteCloser, plan FaultPlan) *FaultyReadWriteCloser {
+	f := &FaultyReadWriteCloser{rwc: rwc, plan: plan}
+	if plan.CutAfterDuration > 0 {
+		f.timer = time.AfterFunc(plan.CutAfterDuration, f.Cut)
+	}
+	return f
+}
+
+// Read reads data from the transport.
+func (f *FaultyReadWriteCloser) Read(p []byte) (int, error) {
+	p, err := f.limit(p)
+	if err != nil {
+		return 0, err
+	}
+	n, err := f.rwc.Read(p)
+
+	f.mtx.Lock()
+	defer f.mtx.Unlock()
+	if f.cut {
+		return 0, ErrFaultInjected
+	}
+	if f.plan.CorruptRead {
+		if off := f.plan.CorruptReadOffset - f.readOffset; off >= 0 && off < n {
+			p[off] ^= 0xff
+		}
+	}
+	f.readOffset += n
+	f.transferred += n
+	return n, err
+}
+
+// Write writes data to the transport.
+//
+// If the transport is cut mid-write, returns a short write & ErrFaultInjected.
+func (f *FaultyReadWriteCloser) Write(p []byte) (int, error) {
+	lp, err := f.limit(p)
+	if err != nil {
+		return 0, err
+	}
+	n, err := f.rwc.Write(lp)
+
+	f.mtx.Lock()
+	f.transferred += n
+	cut := f.cut
+	f.mtx.Unlock()
+	if cut {
+		return n, ErrFaultInjected
+	}
+	if err == nil && n < len(p) {
+		f.Cut()
+		err = ErrFaultInjected
+	}
+	return n, err
+}
+
+// Cut simulates the transport dropping by closing the underlying transport.
+func (f *FaultyReadWriteCloser) Cut() {
+	f.mtx.Lock()
+	alreadyCut := f.cut
+	f.cut = true
+	f.mtx.Unlock()
+	if !alreadyCut {
+		_ = f.rwc.Close()
+	}
+}
+
+// Close closes the transport.
+func (f *FaultyReadWriteCloser) Close() error {
+	if f.timer != nil {
+		f.timer.Stop()
+	}
+	f.mtx.Lock()
+	alreadyCut := f.cut
+	f.cut = true
+	f.mtx.Unlock()
+	if alreadyCut {
+		return nil
+	}
+	return f.rwc.Close()
+}
+
+// limit truncates p to the remaining byte budget.
+// cuts the transport if there is no budget remaining.
+func (f *FaultyReadWriteCloser) limit(p []byte) ([]byte, error) {
+	f.mtx.Lock()
+	cut := f.cut
+	remaining := f.plan.CutAfterBytes - f.transferred
+	f.mtx.Unlock()
+	if cut {
+		return nil, ErrFaultInjected
+	}
+	if f.plan.CutAfterBytes <= 0 {
+		return p, nil
+	}
+	if remaining <= 0 {
+		f.Cut()
+		return nil, ErrFaultInjected
+	}
+	if len(p) > remaining {
+		p = p[:remaining]
+	}
+	return p, nil
+}
+
+// _ is a type assertion
+var _ io.ReadWriteCloser = ((*FaultyReadWriteCloser)(nil))
@@ -0,0 +1,72 @@
+package srpc
+
+import (
+	"bytes"
+	"encoding/hex"
+	"errors"
+	"testing"
+)
+
+// goldenPackets are framed packets as written by the Go and TS implementations.
+var goldenPackets = []struct {
+	name   string
+	pkt    *Packet
+	framed string
+}{{
+	name:   "call start with data",
+	pkt:    NewCallStartPacket("svc", "m", []byte("hi"), false),
+	framed: "0e000000" + "0a0c" + "0a03737663" + "12016d" + "1a026869",
+}, {
+	name:   "call start with empty data",
+	pkt:    NewCallStartPacket("svc", "m", nil, true),
+	framed: "0c000000" + "0a0a" + "0a03737663" + "12016d" + "2001",
+}, {
+	name:   "call data",
+	pkt:    NewCallDataPacket([]byte("hi"), false, false, nil),
+	framed: "06000000" + "1204" + "0a026869",
+}, {
+	name:   "call data with empty data",
+	pkt:    NewCallDataPacket(nil, true, false, nil),
+	framed: "04000000" + "1202" + "1001",
+}, {
+	name:   "call data complete",
+	pkt:    NewCallDataPacket(nil, false, true, nil),
+	framed: "04000000" + "1202" + "1801",
+}, {
+	name:   "call data error",
+	pkt:    NewCallDataPacket(nil, false, false, errors.New("e")),
+	framed: "07000000" + "1205" + "1801" + "220165",
+}}
+
+func TestGoldenPackets_Encode(t *testing.T) {
+	for _, g := range goldenPackets {
+		rwc := &bufferRWC{}
+		if err := NewPacketReadWriter(rwc).WritePacket(g.pkt); err != nil {
+			t.Fatal(err.Error())
+		}
+		if out := hex.EncodeToString(rwc.Bytes()); out != g.framed {
+			t.Errorf("%s: expected %s got %s", g.name, g.framed, out)
+		}
+	}
+}
+
+func TestGoldenPackets_Decode(t *testing.T) {
+	for _, g := range goldenPackets {
+		framed, err := hex.DecodeString(g.framed)
+		if err != nil {
+			t.Fatal(err.Error())
+		}
+		rwc := &bufferRWC{Buffer: *bytes.NewBuffer(framed)}
+		var received []*Packet
+		err = NewPacketReadWriter(rwc).ReadToHandler(func(pkt *Packet) error {
+			received = append(received, pkt)
+			return nil
+		})
+		if err != nil {
+			t.Fatalf("%s: %v", g.name, err)
+		}
+		if len(received) != 1 || !received[0].EqualVT(g.pkt) {
+			t.Errorf("%s: expected %v got %v", g.name, g.pkt, received)
+		}
+	}
+}
@@ -0,0 +1,258 @@
+package srpc
+
+import (
+	"context"
+	"io"
+	"sync"
+	"testing"
+)
+
+// interopService is the service ID used by the interop tests.
+const interopService = "interop.Service"
+
+// interopHandler implements the four rpc patterns with RawMessage.
+type interopHandler struct{}
+
+// GetServiceID returns the ID of the service.
+func (interopHandler) GetServiceID() string { return interopService }
+
+// GetMethodIDs returns the list of methods for the service.
+func (interopHandler) GetMethodIDs() []string {
+	return []string{"Unary", "ServerStream", "ClientStream", "BidiStream"}
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+func (interopHandler) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	switch methodID {
+	case "Unary":
+		var msg RawMessage
+		if err := strm.MsgRecv(&msg); err != nil {
+			return true, err
+		}
+		return true, strm.MsgSend(&msg)
+	case "ServerStream":
+		var msg RawMessage
+		if err := strm.MsgRecv(&msg); err != nil {
+			return true, err
+		}
+		for _, out := range []RawMessage{msg, nil, msg} {
+			out := out
+			if err := strm.MsgSend(&out); err != nil {
+				return true, err
+			}
+		}
+		return true, nil
+	case "ClientStream":
+		var count byte
+		for {
+			var msg RawMessage
+			if err := strm.MsgRecv(&msg); err != nil {
+				if err != io.EOF {
+					return true, err
+				}
+				break
+			}
+			count++
+		}
+		out := RawMessage{count}
+		return true, strm.MsgSend(&out)
+	case "BidiStream":
+		for {
+			var msg RawMessage
+			if err := strm.MsgRecv(&msg); err != nil {
+				if err == io.EOF {
+					return true, nil
+				}
+				return true, err
+			}
+			if err := strm.MsgSend(&msg); err != nil {
+				return true, err
+			}
+		}
+	default:
+		return false, nil
+	}
+}
+
+// recordWriter is a Writer which records all written packets.
+type recordWriter struct {
+	mtx  sync.Mutex
+	pkts []*Packet
+}
+
+// WritePacket writes a packet to the remote.
+func (w *recordWriter) WritePacket(p *Packet) error {
+	w.mtx.Lock()
+	w.pkts = append(w.pkts, p)
+	w.mtx.Unlock()
+	return nil
+}
+
+// Close closes the writer.
+func (w *recordWriter) Close() error { return nil }
+
+// assertPackets checks the recorded packets match the expected sequence.
+func (w *recordWriter) assertPackets(t *testing.T, expected []*Packet) {
+	w.mtx.Lock()
+	defer w.mtx.Unlock()
+	if len(w.pkts) != len(expected) {
+		t.Fatalf("expected %d packets got %d: %v", len(expected), len(w.pkts), w.pkts)
+	}
+	for i, pkt := range w.pkts {
+		if !pkt.EqualVT(expected[i]) {
+			t.Fatalf("packet %d: expected %v got %v", i, expected[i], pkt)
+		}
+	}
+}
+
+// interopCallData constructs a CallData packet as written by the Go and TS
+// implementations: empty messages set data_is_zero.
+func interopCallData(data string) *Packet {
+	return NewCallDataPacket([]byte(data), len(data) == 0, false, nil)
+}
+
+// interopComplete is the packet ending a call without an error.
+var interopComplete = NewCallDataPacket(nil, false, true, nil)
+
+// TestInterop_Server drives the server with the packet sequences produced by
+// the Go and TS clients and checks the packets written in response.
+func TestInterop_Server(t *testing.T) {
+	cases := []struct {
+		name     string
+		in       []*Packet
+		expected []*Packet
+	}{{
+		name:     "unary",
+		in:       []*Packet{NewCallStartPacket(interopService, "Unary", []byte("hello"), false)},
+		expected: []*Packet{interopCallData("hello"), interopComplete},
+	}, {
+		name:     "unary empty message",
+		in:       []*Packet{NewCallStartPacket(interopService, "Unary", nil, true)},
+		expected: []*Packet{interopCallData(""), interopComplete},
+	}, {
+		name: "server stream",
+		in: []*Packet{
+			NewCallStartPacket(interopService, "ServerStream", []byte("hello"), false),
+			interopComplete,
+		},
+		expected: []*Packet{interopCallData("hello"), interopCallData(""), interopCallData("hello"), interopComplete},
+	}, {
+		name: "client stream",
+		in: []*Packet{
+			NewCallStartPacket(interopService, "ClientStream", nil, false),
+			interopCallData("a"),
+			interopCallData(""),
+			interopCallData("b"),
+			interopComplete,
+		},
+		expected: []*Packet{interopCallData("\x03"), interopComplete},
+	}, {
+		name: "bidi stream",
+		in: []*Packet{
+			NewCallStartPacket(interopService, "BidiStream", nil, false),
+			interopCallData("a"),
+			interopCallData(""),
+			interopComplete,
+		},
+		expected: []*Packet{interopCallData("a"), interopCallData(""), interopComplete},
+	}, {
+		name: "bidi stream canceled",
+		in: []*Packet{
+			NewCallStartPacket(interopService, "BidiStream", nil, false),
+			interopCallData("a"),
+			NewCallDataPacket(nil, false, true, context.Canceled),
+		},
+		expected: []*Packet{interopCallData("a"), NewCallDataPacket(nil, false, true, context.Canceled)},
+	}}
+
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			writer := &recordWriter{}
+			rpc := NewServerRPC(ctx, mux)
+			rpc.SetWriter(writer)
+			for _, pkt := range tc.in {
+				if err := rpc.HandlePacket(pkt); err != nil {
+					t.Fatal(err.Error())
+				}
+			}
+			_ = rpc.Wait(ctx)
+			writer.assertPackets(t, tc.expected)
+		})
+	}
+}
+
+// TestInterop_Client drives the client with the packet sequences produced by
+// the Go and TS servers and checks the messages read.
+func TestInterop_Client(t *testing.T) {
+	cases := []struct {
+		name     string
+		firstMsg []byte
+		start    *Packet
+		in       []*Packet
+		expected []string
+		err      error
+	}{{
+		name:     "unary",
+		firstMsg: []byte("hello"),
+		start:    NewCallStartPacket(interopService, "Unary", []byte("hello"), false),
+		in:       []*Packet{interopCallData("hello"), interopComplete},
+		expected: []string{"hello"},
+	}, {
+		name:     "unary empty message",
+		firstMsg: []byte{},
+		start:    NewCallStartPacket(interopService, "Unary", nil, true),
+		in:       []*Packet{interopCallData(""), interopComplete},
+		expected: []string{""},
+	}, {
+		name:     "server stream",
+		firstMsg: []byte("hello"),
+		start:    NewCallStartPacket(interopService, "ServerStream", []byte("hello"), false),
+		in:       []*Packet{interopCallData("hello"), interopCallData(""), interopCallData("hello"), interopComplete},
+		expected: []string{"hello", "", "hello"},
+	}, {
+		name:     "bidi stream canceled",
+		start:    NewCallStartPacket(interopService, "BidiStream", nil, false),
+		in:       []*Packet{interopCallData("a"), NewCallDataPacket(nil, false, true, context.Canceled)},
+		expected: []string{"a"},
+		err:      context.Canceled,
+	}}
+
+	ctx := context.Background()
+	for _, tc := range cases {
+		t.Run(tc.name, func(t *testing.T) {
+			writer := &recordWriter{}
+			rpc := NewClientRPC(ctx, interopService, tc.start.GetCallStart().GetRpcMethod())
+			if err := rpc.Start(writer, tc.firstMsg != nil, tc.firstMsg); err != nil {
+				t.Fatal(err.Error())
+			}
+			writer.assertPackets(t, []*Packet{tc.start})
+			for _, pkt := range tc.in {
+				if err := rpc.HandlePacket(pkt); err != nil {
+					t.Fatal(err.Error())
+				}
+			}
+			for _, msg := range tc.expected {
+				data, err := rpc.ReadOne()
+				if err != nil {
+					t.Fatal(err.Error())
+				}
+				if string(data) != msg {
+					t.Fatalf("expected %q got %q", msg, string(data))
+				}
+			}
+			_, err := rpc.ReadOne()
+			expectedErr := tc.err
+			if expectedErr == nil {
+				expectedErr = io.EOF
+			}
+			if err == nil || err.Error() != expectedErr.Error() {
+				t.Fatalf("expected %v got %v", expectedErr, err)
+			}
+		})
+	}
+}
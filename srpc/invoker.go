@@ -0,0 +1,103 @@
+package srpc
+
+import "context"
+
+// InvokerFunc implements Invoker with a function.
+type InvokerFunc func(serviceID, methodID string, strm Stream) (bool, error)
+
+// InvokeMethod invokes the method matching the service & method ID.
+// Returns false, nil if not found.
+func (f InvokerFunc) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	return f(serviceID, methodID, strm)
+}
+
+// InvokerOrElse constructs an Invoker which tries invoker, and if it did not
+// handle the method, tries fallback.
+//
+// fallback is not called if invoker handled the method, even with an error.
+func InvokerOrElse(invoker, fallback Invoker) Invoker {
+	return &orElseInvoker{invoker: invoker, fallback: fallback}
+}
+
+// orElseInvoker implements InvokerOrElse.
+type orElseInvoker struct {
+	// invoker is tried first
+	invoker Invoker
+	// fallback is tried if invoker did not handle the method
+	fallback Invoker
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+// Returns false, nil if not found.
+func (i *orElseInvoker) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	if handled, err := i.invoker.InvokeMethod(serviceID, methodID, strm); handled {
+		return true, err
+	}
+	return i.fallback.InvokeMethod(serviceID, methodID, strm)
+}
+
+// HasServiceMethod checks if the <service, method> pair has a handler.
+func (i *orElseInvoker) HasServiceMethod(serviceID, methodID string) bool {
+	return invokerHasServiceMethod(i.invoker, serviceID, methodID) ||
+		invokerHasServiceMethod(i.fallback, serviceID, methodID)
+}
+
+// InvokerMapErr constructs an Invoker which transforms errors returned by the
+// handlers of invoker with mapErr before they are sent to the remote.
+//
+// mapErr is not called if the handler returned nil.
+func InvokerMapErr(invoker Invoker, mapErr func(err error) error) Invoker {
+	return &mapErrInvoker{invoker: invoker, mapErr: mapErr}
+}
+
+// mapErrInvoker implements InvokerMapErr.
+type mapErrInvoker struct {
+	// invoker is the underlying invoker
+	invoker Invoker
+	// mapErr transforms handler errors
+	mapErr func(err error) error
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+// Returns false, nil if not found.
+func (i *mapErrInvoker) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	handled, err := i.invoker.InvokeMethod(serviceID, methodID, strm)
+	if err != nil {
+		err = i.mapErr(err)
+	}
+	return handled, err
+}
+
+// HasServiceMethod checks if the <service, method> pair has a handler.
+func (i *mapErrInvoker) HasServiceMethod(serviceID, methodID string) bool {
+	return invokerHasServiceMethod(i.invoker, serviceID, methodID)
+}
+
+// InvokerRouter constructs an Invoker which selects the Invoker for each call.
+//
+// route is called with the rpc context and the service & method IDs of the
+// CallStart, for example to route each tenant to a separate Mux using the
+// ConnInfo. If route returns nil the method is not found.
+func InvokerRouter(route func(ctx context.Context, serviceID, methodID string) Invoker) Invoker {
+	return InvokerFunc(func(serviceID, methodID string, strm Stream) (bool, error) {
+		invoker := route(strm.Context(), serviceID, methodID)
+		if invoker == nil {
+			return false, nil
+		}
+		return invoker.InvokeMethod(serviceID, methodID, strm)
+	})
+}
+
+// invokerHasServiceMethod checks if the invoker has a handler for the method.
+// Returns true if the invoker is not a QueryableInvoker.
+func invokerHasServiceMethod(invoker Invoker, serviceID, methodID string) bool {
+	q, ok := invoker.(QueryableInvoker)
+	return !ok || q.HasServiceMethod(serviceID, methodID)
+}
+
+// _ is a type assertion
+var (
+	_ Invoker          = (InvokerFunc)(nil)
+	_ QueryableInvoker = ((*orElseInvoker)(nil))
+	_ QueryableInvoker = ((*mapErrInvoker)(nil))
+)
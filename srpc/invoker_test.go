@@ -0,0 +1,77 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+func TestInvokerOrElse(t *testing.T) {
+	var fallbackCalls int
+	fallback := InvokerFunc(func(serviceID, methodID string, strm Stream) (bool, error) {
+		fallbackCalls++
+		return true, errors.New("fallback")
+	})
+
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(errors.New("first"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	invoker := InvokerOrElse(mux, fallback)
+
+	// the first invoker short-circuits the fallback.
+	handled, err := invoker.InvokeMethod("test.Service", "Method", nil)
+	if !handled || err == nil || err.Error() != "first" || fallbackCalls != 0 {
+		t.Fatalf("expected first handler got %v: %v", handled, err)
+	}
+
+	// unhandled methods fall back.
+	handled, err = invoker.InvokeMethod("test.Other", "Method", nil)
+	if !handled || err == nil || err.Error() != "fallback" || fallbackCalls != 1 {
+		t.Fatalf("expected fallback handler got %v: %v", handled, err)
+	}
+}
+
+func TestInvokerMapErr(t *testing.T) {
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(errors.New("internal"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	mapped := errors.New("mapped")
+	invoker := InvokerMapErr(mux, func(err error) error { return mapped })
+
+	handled, err := invoker.InvokeMethod("test.Service", "Method", nil)
+	if !handled || err != mapped {
+		t.Fatalf("expected mapped error got %v: %v", handled, err)
+	}
+	handled, err = invoker.InvokeMethod("test.Other", "Method", nil)
+	if handled || err != nil {
+		t.Fatalf("expected not handled got %v: %v", handled, err)
+	}
+	if invoker.(QueryableInvoker).HasServiceMethod("test.Other", "Method") {
+		t.Fatal("expected no handler for test.Other")
+	}
+}
+
+func TestInvokerRouter(t *testing.T) {
+	tenantMux := NewMux()
+	if err := tenantMux.Register(newErrHandler(errors.New("tenant"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	invoker := InvokerRouter(func(ctx context.Context, serviceID, methodID string) Invoker {
+		if ctx.Value(testCtxKey{}) == "tenant" {
+			return tenantMux
+		}
+		return nil
+	})
+
+	strm := NewMockStream(context.WithValue(context.Background(), testCtxKey{}, "tenant"))
+	handled, err := invoker.InvokeMethod("test.Service", "Method", strm)
+	if !handled || err == nil || err.Error() != "tenant" {
+		t.Fatalf("expected tenant handler got %v: %v", handled, err)
+	}
+	handled, err = invoker.InvokeMethod("test.Service", "Method", NewMockStream(context.Background()))
+	if handled || err != nil {
+		t.Fatalf("expected not handled got %v: %v", handled, err)
+	}
+}
@@ -0,0 +1,121 @@
+package srpc
+
+import (
+	"context"
+	"sync"
+)
+
+// LimitedClient wraps a Client to cap the number of in-flight rpcs.
+//
+// Calls wait for a free slot (or the context to be canceled) before starting.
+// Streams hold their slot until Close is called or the stream context ends.
+type LimitedClient struct {
+	// client is the underlying client
+	client Client
+	// sem contains one element per in-flight rpc
+	sem chan struct{}
+}
+
+// NewLimitedClient constructs a new LimitedClient.
+//
+// limit is the max number of in-flight rpcs, if <= 0 defaults to 1.
+func NewLimitedClient(client Client, limit int) *LimitedClient {
+	if limit <= 0 {
+		limit = 1
+	}
+	return &LimitedClient{
+		client: client,
+		sem:    make(chan struct{}, limit),
+	}
+}
+
+// InFlight returns the number of in-flight rpcs.
+func (c *LimitedClient) InFlight() int {
+	return len(c.sem)
+}
+
+// Limit returns the max number of in-flight rpcs.
+func (c *LimitedClient) Limit() int {
+	return cap(c.sem)
+}
+
+// Invoke executes a unary RPC with the remote.
+func (c *LimitedClient) Invoke(ctx context.Context, service, method string, in, out Message) error {
+	if err := c.acquire(ctx); err != nil {
+		return err
+	}
+	defer c.release()
+	return c.client.Invoke(ctx, service, method, in, out)
+}
+
+// NewStream starts a streaming RPC with the remote & returns the stream.
+// firstMsg is optional.
+func (c *LimitedClient) NewStream(ctx context.Context, service, method string, firstMsg Message) (Stream, error) {
+	if err := c.acquire(ctx); err != nil {
+		return nil, err
+	}
+	strm, err := c.client.NewStream(ctx, service, method, firstMsg)
+	if err != nil {
+		c.release()
+		return nil, err
+	}
+	ls := &limitedStream{Stream: strm, release: c.release, done: make(chan struct{})}
+	go ls.releaseOnDone()
+	return ls, nil
+}
+
+// acquire waits for a free slot.
+func (c *LimitedClient) acquire(ctx context.Context) error {
+	select {
+	case <-ctx.Done():
+		return context.Canceled
+	case c.sem <- struct{}{}:
+		return nil
+	}
+}
+
+// release frees a slot.
+func (c *LimitedClient) release() {
+	<-c.sem
+}
+
+// limitedStream releases the LimitedClient slot when the stream ends.
+type limitedStream struct {
+	Stream
+	// release frees the slot
+	release func()
+	// releaseOnce guards release
+	releaseOnce sync.Once
+	// done is closed when Close is called
+	done chan struct{}
+}
+
+// Close closes the stream.
+func (s *limitedStream) Close() error {
+	err := s.Stream.Close()
+	s.releaseOnce.Do(func() {
+		close(s.done)
+		s.release()
+	})
+	return err
+}
+
+// releaseOnDone releases the slot when the stream context ends.
+func (s *limitedStream) releaseOnDone() {
+	select {
+	case <-s.done:
+	case <-s.Stream.Context().Done():
+		s.releaseOnce.Do(s.release)
+	}
+}
+
+// Unwrap returns the underlying Stream.
+func (s *limitedStream) Unwrap() Stream {
+	return s.Stream
+}
+
+// _ is a type assertion
+var (
+	_ Client = ((*LimitedClient)(nil))
+	_ Stream = ((*limitedStream)(nil))
+)
@@ -0,0 +1,25 @@
+package srpc
+
+import "context"
+
+// LinkContext returns a context which is canceled when either ctx or other is
+// canceled.
+//
+// The returned context carries the values and deadline of ctx. Useful to tie a
+// per-rpc context to a server-wide shutdown signal. The CancelFunc must be
+// called to release the goroutine watching other.
+func LinkContext(ctx, other context.Context) (context.Context, context.CancelFunc) {
+	linkedCtx, linkedCtxCancel := context.WithCancel(ctx)
+	if other.Done() == nil {
+		// other can never be canceled
+		return linkedCtx, linkedCtxCancel
+	}
+	go func() {
+		select {
+		case <-other.Done():
+			linkedCtxCancel()
+		case <-linkedCtx.Done():
+		}
+	}()
+	return linkedCtx, linkedCtxCancel
+}
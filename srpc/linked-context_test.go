@@ -0,0 +1,31 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+)
+
+func TestLinkContext(t *testing.T) {
+	for _, cancelOther := range []bool{false, true} {
+		ctx, ctxCancel := context.WithCancel(context.WithValue(context.Background(), testCtxKey{}, "value"))
+		other, otherCancel := context.WithCancel(context.Background())
+		linked, linkedCancel := LinkContext(ctx, other)
+
+		if linked.Value(testCtxKey{}) != "value" {
+			t.Fatal("expected the linked context to carry the values of ctx")
+		}
+		if linked.Err() != nil {
+			t.Fatal("expected the linked context to be active")
+		}
+		if cancelOther {
+			otherCancel()
+		} else {
+			ctxCancel()
+		}
+		<-linked.Done()
+
+		linkedCancel()
+		ctxCancel()
+		otherCancel()
+	}
+}
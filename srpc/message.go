@@ -5,3 +5,40 @@ type Message interface {
 	MarshalVT() ([]byte, error)
 	UnmarshalVT([]byte) error
 }
+
+// sizedMessage is a Message which can compute its encoded size.
+type sizedMessage interface {
+	SizeVT() int
+}
+
+// marshalMessage encodes a message to send to the remote.
+//
+// Returns ErrMessageTooLarge if the CallData packet containing the message
+// exceeds the max message size. Checks the size before encoding if the message
+// implements SizeVT.
+func marshalMessage(msg Message) ([]byte, error) {
+	if sm, ok := msg.(sizedMessage); ok && callDataPacketSize(sm.SizeVT()) > int(maxMessageSize) {
+		return nil, ErrMessageTooLarge
+	}
+	data, err := msg.MarshalVT()
+	if err != nil {
+		return nil, err
+	}
+	if callDataPacketSize(len(data)) > int(maxMessageSize) {
+		return nil, ErrMessageTooLarge
+	}
+	return data, nil
+}
+
+// callDataPacketSize returns the max encoded size of a CallData packet with a
+// message of n bytes, as checked by the remote against the max message size.
+func callDataPacketSize(n int) int {
+	// data field and the complete flag, or the data_is_zero flag if empty.
+	size := 2
+	if n > 0 {
+		size += 1 + sov(uint64(n)) + n
+	} else {
+		size += 2
+	}
+	return 1 + sov(uint64(size)) + size
+}
@@ -0,0 +1,231 @@
+package srpc
+
+import (
+	"context"
+	"io"
+	"sync"
+)
+
+// MockStream implements an in-memory Stream backed by message queues.
+//
+// Intended for testing handlers without a transport: push incoming messages
+// with PushIncoming or PushMsg, call the handler, and check the messages sent
+// by the handler with TakeOutgoing or TakeMsg. Concurrency safe.
+type MockStream struct {
+	// ctx is canceled when the stream is closed
+	ctx context.Context
+	// ctxCancel cancels ctx
+	ctxCancel context.CancelFunc
+	// mtx guards below fields
+	mtx sync.Mutex
+	// incoming is the queue of messages to receive
+	incoming [][]byte
+	// incomingClosed indicates CloseIncoming was called
+	incomingClosed bool
+	// incomingErr is the error to return after incoming is drained, if set
+	incomingErr error
+	// incomingWake is closed and replaced when incoming changes
+	incomingWake chan struct{}
+	// recvClosed is closed when CloseIncoming is called
+	recvClosed chan struct{}
+	// outgoing is the queue of sent messages
+	outgoing [][]byte
+	// sendClosed indicates CloseSend, Cancel, or Close was called
+	sendClosed bool
+	// canceled indicates Cancel was called
+	canceled bool
+}
+
+// NewMockStream constructs a new MockStream.
+func NewMockStream(ctx context.Context) *MockStream {
+	s := &MockStream{
+		incomingWake: make(chan struct{}),
+		recvClosed:   make(chan struct{}),
+	}
+	s.ctx, s.ctxCancel = context.WithCancel(ctx)
+	return s
+}
+
+// PushIncoming queues an encoded message to be received with MsgRecv.
+// Returns ErrCompleted if CloseIncoming was called.
+func (s *MockStream) PushIncoming(data []byte) error {
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	if s.incomingClosed {
+		return ErrCompleted
+	}
+	s.incoming = append(s.incoming, append([]byte(nil), data...))
+	s.wakeIncoming()
+	return nil
+}
+
+// PushMsg encodes and queues a message to be received with MsgRecv.
+func (s *MockStream) PushMsg(msg Message) error {
+	data, err := msg.MarshalVT()
+	if err != nil {
+		return err
+	}
+	return s.PushIncoming(data)
+}
+
+// CloseIncoming closes the incoming queue w/ optional error.
+//
+// MsgRecv returns the queued messages, then err or io.EOF if err is nil.
+func (s *MockStream) CloseIncoming(err error) {
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	if s.incomingClosed {
+		return
+	}
+	s.incomingClosed, s.incomingErr = true, err
+	close(s.recvClosed)
+	s.wakeIncoming()
+}
+
+// TakeOutgoing returns and clears the encoded messages sent with MsgSend.
+func (s *MockStream) TakeOutgoing() [][]byte {
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	out := s.outgoing
+	s.outgoing = nil
+	return out
+}
+
+// TakeMsg removes the first message sent with MsgSend and parses it into msg.
+// Returns false if no messages were sent.
+func (s *MockStream) TakeMsg(msg Message) (bool, error) {
+	s.mtx.Lock()
+	if len(s.outgoing) == 0 {
+		s.mtx.Unlock()
+		return false, nil
+	}
+	data := s.outgoing[0]
+	s.outgoing = s.outgoing[1:]
+	s.mtx.Unlock()
+	return true, msg.UnmarshalVT(data)
+}
+
+// Context is canceled when the Stream is no longer valid.
+func (s *MockStream) Context() context.Context {
+	return s.ctx
+}
+
+// MsgSend sends the message to the remote.
+// Returns ErrCompleted if the send side was closed.
+func (s *MockStream) MsgSend(msg Message) error {
+	data, err := msg.MarshalVT()
+	if err != nil {
+		return err
+	}
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	if s.sendClosed {
+		return ErrCompleted
+	}
+	s.outgoing = append(s.outgoing, append([]byte(nil), data...))
+	return nil
+}
+
+// MsgRecv receives an incoming message from the remote.
+// Parses the message into the object at msg.
+func (s *MockStream) MsgRecv(msg Message) error {
+	return s.MsgRecvContext(s.ctx, msg)
+}
+
+// MsgRecvContext receives an incoming message from the remote.
+// ctx must be derived from the stream Context.
+func (s *MockStream) MsgRecvContext(ctx context.Context, msg Message) error {
+	for {
+		s.mtx.Lock()
+		if len(s.incoming) != 0 {
+			data := s.incoming[0]
+			s.incoming = s.incoming[1:]
+			s.mtx.Unlock()
+			return msg.UnmarshalVT(data)
+		}
+		if s.incomingClosed {
+			err := s.incomingErr
+			s.mtx.Unlock()
+			if err == nil {
+				err = io.EOF
+			}
+			return err
+		}
+		wake := s.incomingWake
+		s.mtx.Unlock()
+
+		select {
+		case <-ctx.Done():
+			return context.Canceled
+		case <-wake:
+		}
+	}
+}
+
+// RecvClosed returns a channel closed when CloseIncoming is called.
+func (s *MockStream) RecvClosed() <-chan struct{} {
+	return s.recvClosed
+}
+
+// IsRecvClosed checks if CloseIncoming was called.
+func (s *MockStream) IsRecvClosed() bool {
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	return s.incomingClosed
+}
+
+// IsSendClosed checks if CloseSend or Close was called.
+func (s *MockStream) IsSendClosed() bool {
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	return s.sendClosed
+}
+
+// Flush writes any buffered messages to the remote.
+// Messages are not buffered: does nothing.
+func (s *MockStream) Flush() error {
+	return nil
+}
+
+// CloseSend signals to the remote that we will no longer send any messages.
+func (s *MockStream) CloseSend() error {
+	s.mtx.Lock()
+	s.sendClosed = true
+	s.mtx.Unlock()
+	return nil
+}
+
+// Cancel signals to the remote to cancel the rpc and closes the send side.
+// Does nothing if the send side is already closed.
+func (s *MockStream) Cancel() error {
+	s.mtx.Lock()
+	if !s.sendClosed {
+		s.sendClosed, s.canceled = true, true
+	}
+	s.mtx.Unlock()
+	return nil
+}
+
+// IsCanceled checks if Cancel was called before the send side was closed.
+func (s *MockStream) IsCanceled() bool {
+	s.mtx.Lock()
+	defer s.mtx.Unlock()
+	return s.canceled
+}
+
+// Close closes the stream.
+func (s *MockStream) Close() error {
+	_ = s.CloseSend()
+	s.ctxCancel()
+	return nil
+}
+
+// wakeIncoming wakes any waiting MsgRecv calls.
+// Caller must hold mtx.
+func (s *MockStream) wakeIncoming() {
+	close(s.incomingWake)
+	s.incomingWake = make(chan struct{})
+}
+
+// _ is a type assertion
+var _ Stream = ((*MockStream)(nil))
@@ -0,0 +1,70 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+func TestMockStream(t *testing.T) {
+	strm := NewMockStream(context.Background())
+	for _, msg := range []string{"a", "", "b"} {
+		if err := strm.PushIncoming([]byte(msg)); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	strm.CloseIncoming(nil)
+
+	handled, err := interopHandler{}.InvokeMethod(interopService, "ClientStream", strm)
+	if err != nil || !handled {
+		t.Fatalf("expected handled got %v: %v", handled, err)
+	}
+	var out RawMessage
+	if ok, err := strm.TakeMsg(&out); !ok || err != nil {
+		t.Fatalf("expected message got %v: %v", ok, err)
+	}
+	if len(out) != 1 || out[0] != 3 {
+		t.Fatalf("expected count 3 got %v", out)
+	}
+	if rest := strm.TakeOutgoing(); len(rest) != 0 {
+		t.Fatalf("expected no more messages got %v", rest)
+	}
+
+	// errors are returned after the queued messages.
+	strm = NewMockStream(context.Background())
+	recvErr := errors.New("remote failed")
+	msg := RawMessage("hello")
+	if err := strm.PushMsg(&msg); err != nil {
+		t.Fatal(err.Error())
+	}
+	strm.CloseIncoming(recvErr)
+	if !strm.IsRecvClosed() {
+		t.Fatal("expected recv closed")
+	}
+	handled, err = interopHandler{}.InvokeMethod(interopService, "BidiStream", strm)
+	if !handled || err != recvErr {
+		t.Fatalf("expected %v got %v", recvErr, err)
+	}
+	if outgoing := strm.TakeOutgoing(); len(outgoing) != 1 || string(outgoing[0]) != "hello" {
+		t.Fatalf("expected echo got %v", outgoing)
+	}
+}
+
+func TestStreamHelpers_Unwrap(t *testing.T) {
+	mock := NewMockStream(context.Background())
+	strm := NewRecvTimeoutStream(mock, time.Second)
+	if IsStreamRecvClosed(strm) || IsStreamSendClosed(strm) {
+		t.Fatal("expected the stream to be open")
+	}
+	mock.CloseIncoming(nil)
+	if !IsStreamRecvClosed(strm) {
+		t.Fatal("expected recv closed")
+	}
+	if err := CancelStream(strm); err != nil {
+		t.Fatal(err.Error())
+	}
+	if !mock.IsCanceled() || !IsStreamSendClosed(strm) {
+		t.Fatal("expected the stream to be canceled")
+	}
+}
@@ -0,0 +1,14 @@
+package srpc
+
+// MsgSendBatch sends the messages to the remote and flushes the stream.
+//
+// Each message is sent as a separate CallData packet. If the transport uses
+// FlushBuffered the packets are coalesced into as few writes as possible.
+func MsgSendBatch[T Message](strm Stream, msgs []T) error {
+	for _, msg := range msgs {
+		if err := strm.MsgSend(msg); err != nil {
+			return err
+		}
+	}
+	return FlushStream(strm)
+}
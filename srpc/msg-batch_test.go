@@ -0,0 +1,74 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+	"time"
+)
+
+// countRWC is a ReadWriteCloser which counts and discards writes.
+type countRWC struct {
+	discardRWC
+	writes int
+}
+
+// Write counts and discards the data.
+func (c *countRWC) Write(p []byte) (int, error) {
+	c.writes++
+	return len(p), nil
+}
+
+// newBatchTestStream constructs a MsgStream writing to a countRWC.
+func newBatchTestStream(policy FlushPolicy) (*MsgStream, *countRWC) {
+	rwc := &countRWC{}
+	prw := NewPacketReadWriter(rwc)
+	prw.SetFlushPolicy(policy, time.Hour)
+	return NewMsgStream(context.Background(), prw, nil), rwc
+}
+
+// newBatchTestMsgs constructs the messages to send in a batch.
+func newBatchTestMsgs() []*RawMessage {
+	msgs := make([]*RawMessage, 100)
+	for i := range msgs {
+		msgs[i] = &RawMessage{'h', 'e', 'l', 'l', 'o'}
+	}
+	return msgs
+}
+
+func TestMsgSendBatch(t *testing.T) {
+	strm, rwc := newBatchTestStream(FlushBuffered)
+	if err := MsgSendBatch(strm, newBatchTestMsgs()); err != nil {
+		t.Fatal(err.Error())
+	}
+	if rwc.writes != 1 {
+		t.Fatalf("expected 1 write got %d", rwc.writes)
+	}
+}
+
+func BenchmarkMsgSend_Sequential(b *testing.B) {
+	strm, rwc := newBatchTestStream(FlushImmediate)
+	msgs := newBatchTestMsgs()
+	b.ReportAllocs()
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		for _, msg := range msgs {
+			if err := strm.MsgSend(msg); err != nil {
+				b.Fatal(err.Error())
+			}
+		}
+	}
+	b.ReportMetric(float64(rwc.writes)/float64(b.N), "writes/op")
+}
+
+func BenchmarkMsgSend_Batch(b *testing.B) {
+	strm, rwc := newBatchTestStream(FlushBuffered)
+	msgs := newBatchTestMsgs()
+	b.ReportAllocs()
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		if err := MsgSendBatch(strm, msgs); err != nil {
+			b.Fatal(err.Error())
+		}
+	}
+	b.ReportMetric(float64(rwc.writes)/float64(b.N), "writes/op")
+}
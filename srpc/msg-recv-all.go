@@ -0,0 +1,25 @@
+package srpc
+
+import "io"
+
+// MsgRecvAll receives messages until the remote closes its send side.
+//
+// newMsg constructs a message to parse into. Returns ErrTooManyMessages if
+// more than max messages are received, if max <= 0 there is no limit. Returns
+// the received messages, which may be empty, if the remote closed cleanly.
+func MsgRecvAll[T Message](strm Stream, newMsg func() T, max int) ([]T, error) {
+	msgs := make([]T, 0)
+	for {
+		msg := newMsg()
+		if err := strm.MsgRecv(msg); err != nil {
+			if err == io.EOF {
+				return msgs, nil
+			}
+			return nil, err
+		}
+		if max > 0 && len(msgs) == max {
+			return nil, ErrTooManyMessages
+		}
+		msgs = append(msgs, msg)
+	}
+}
@@ -0,0 +1,47 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+)
+
+// sendAndClose sends the messages to the stream then closes the send side.
+func sendAndClose(t *testing.T, strm Stream, bodies ...string) {
+	for _, body := range bodies {
+		msg := RawMessage(body)
+		if err := strm.MsgSend(&msg); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if err := strm.CloseSend(); err != nil {
+		t.Fatal(err.Error())
+	}
+}
+
+func TestMsgRecvAll(t *testing.T) {
+	ctx := context.Background()
+	newMsg := func() *RawMessage { return new(RawMessage) }
+
+	s1, s2 := NewPipeStream(ctx)
+	sendAndClose(t, s1, "a", "b")
+	msgs, err := MsgRecvAll(s2, newMsg, 2)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(msgs) != 2 || string(*msgs[0]) != "a" || string(*msgs[1]) != "b" {
+		t.Fatalf("unexpected messages: %v", msgs)
+	}
+
+	s1, s2 = NewPipeStream(ctx)
+	sendAndClose(t, s1)
+	msgs, err = MsgRecvAll(s2, newMsg, 2)
+	if err != nil || msgs == nil || len(msgs) != 0 {
+		t.Fatalf("expected no messages got %v, %v", msgs, err)
+	}
+
+	s1, s2 = NewPipeStream(ctx)
+	sendAndClose(t, s1, "a", "b", "c")
+	if _, err := MsgRecvAll(s2, newMsg, 2); err != ErrTooManyMessages {
+		t.Fatalf("expected %v got %v", ErrTooManyMessages, err)
+	}
+}
@@ -0,0 +1,25 @@
+package srpc
+
+import "io"
+
+// MsgSendAll sends messages produced by next until it returns an error.
+//
+// next returns io.EOF when there are no more messages. Any other error from
+// next stops sending and is returned: return it from the handler to end the
+// rpc with the error. Returns the number of messages sent.
+func MsgSendAll[T Message](strm Stream, next func() (T, error)) (int, error) {
+	var sent int
+	for {
+		msg, err := next()
+		if err != nil {
+			if err == io.EOF {
+				return sent, nil
+			}
+			return sent, err
+		}
+		if err := strm.MsgSend(msg); err != nil {
+			return sent, err
+		}
+		sent++
+	}
+}
@@ -0,0 +1,39 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"io"
+	"testing"
+)
+
+func TestMsgSendAll(t *testing.T) {
+	ctx := context.Background()
+	nextErr := errors.New("next failed")
+	for _, expected := range []error{io.EOF, nextErr} {
+		s1, s2 := NewPipeStream(ctx)
+		var calls int
+		sent, err := MsgSendAll(s1, func() (*RawMessage, error) {
+			calls++
+			if calls == 3 {
+				return nil, expected
+			}
+			msg := RawMessage("a")
+			return &msg, nil
+		})
+		if sent != 2 {
+			t.Fatalf("expected 2 messages sent but got %d", sent)
+		}
+		if expected == io.EOF && err != nil {
+			t.Fatal(err.Error())
+		}
+		if expected != io.EOF && err != expected {
+			t.Fatalf("expected %v but got %v", expected, err)
+		}
+		sendAndClose(t, s1)
+		msgs, err := MsgRecvAll(s2, func() *RawMessage { return new(RawMessage) }, 0)
+		if err != nil || len(msgs) != 2 {
+			t.Fatalf("unexpected messages: %v, %v", msgs, err)
+		}
+	}
+}
@@ -2,27 +2,74 @@ package srpc
 
 import (
 	"context"
-	"io"
+	"fmt"
+	"sync"
+	"sync/atomic"
 )
 
+// DefaultRecvBufferSize is the default number of incoming messages buffered
+// before the read pump blocks waiting for MsgRecv.
+const DefaultRecvBufferSize = 5
+
+// newRecvBuffer constructs the incoming message buffer channel.
+// If size <= 0 uses DefaultRecvBufferSize.
+func newRecvBuffer(size int) chan []byte {
+	if size <= 0 {
+		size = DefaultRecvBufferSize
+	}
+	return make(chan []byte, size)
+}
+
+// isChanClosed checks if the channel is closed without blocking.
+func isChanClosed(ch <-chan struct{}) bool {
+	select {
+	case <-ch:
+		return true
+	default:
+		return false
+	}
+}
+
+// MsgStreamReader reads the incoming messages for a MsgStream.
+type MsgStreamReader interface {
+	// ReadOneContext reads a single message and returns.
+	//
+	// returns context.Canceled if ctx is canceled.
+	// returns io.EOF if the stream ended.
+	ReadOneContext(ctx context.Context) ([]byte, error)
+	// RecvClosed returns a channel closed when the remote closed its send side.
+	RecvClosed() <-chan struct{}
+}
+
 // MsgStream implements the stream interface passed to implementations.
+//
+// If writing to the writer fails, cancels the stream context and returns the
+// error as a TransportError from all subsequent sends.
 type MsgStream struct {
 	// ctx is the stream context
 	ctx context.Context
+	// ctxCancel cancels ctx
+	ctxCancel context.CancelFunc
 	// writer is the stream writer
 	writer Writer
-	// dataCh is the incoming data channel.
-	dataCh chan []byte
+	// reader is the incoming message reader.
+	reader MsgStreamReader
+	// sendClosed is set to 1 after CloseSend or Close.
+	sendClosed uint32
+	// writeErrMtx guards writeErr
+	writeErrMtx sync.Mutex
+	// writeErr is the first error returned by the writer.
+	writeErr error
 }
 
-// NewMsgStream constructs a new Stream with a ClientRPC.
-// dataCh should be closed when no more messages will arrive.
-func NewMsgStream(ctx context.Context, writer Writer, dataCh chan []byte) *MsgStream {
-	return &MsgStream{
-		ctx:    ctx,
+// NewMsgStream constructs a new Stream with a ClientRPC or ServerRPC.
+func NewMsgStream(ctx context.Context, writer Writer, reader MsgStreamReader) *MsgStream {
+	strm := &MsgStream{
 		writer: writer,
-		dataCh: dataCh,
+		reader: reader,
 	}
+	strm.ctx, strm.ctxCancel = context.WithCancel(ctx)
+	return strm
 }
 
 // Context is canceled when the Stream is no longer valid.
@@ -32,45 +79,170 @@ func (r *MsgStream) Context() context.Context {
 
 // MsgSend sends the message to the remote.
 func (r *MsgStream) MsgSend(msg Message) error {
+	if err := r.getWriteErr(); err != nil {
+		return err
+	}
 	select {
 	case <-r.ctx.Done():
 		return context.Canceled
 	default:
 	}
 
-	msgData, err := msg.MarshalVT()
+	msgData, err := marshalMessage(msg)
 	if err != nil {
 		return err
 	}
 	outPkt := NewCallDataPacket(msgData, len(msgData) == 0, false, nil)
-	return r.writer.WritePacket(outPkt)
+	return r.handleWriteErr(r.writer.WritePacket(outPkt))
 }
 
 // MsgRecv receives an incoming message from the remote.
 // Parses the message into the object at msg.
+//
+// Returns io.EOF if the remote completed the rpc, the remote error if the rpc
+// failed, or a TransportError if the transport closed before the rpc completed.
+// Returns context.Canceled if the stream Context is canceled.
 func (r *MsgStream) MsgRecv(msg Message) error {
+	return r.MsgRecvContext(r.ctx, msg)
+}
+
+// MsgRecvContext receives an incoming message from the remote.
+//
+// ctx must be derived from the stream Context.
+// Returns context.Canceled if ctx is canceled.
+func (r *MsgStream) MsgRecvContext(ctx context.Context, msg Message) error {
+	data, err := r.reader.ReadOneContext(ctx)
+	if err != nil {
+		return err
+	}
+	return msg.UnmarshalVT(data)
+}
+
+// RecvClosed returns a channel closed when the remote closed its send side.
+func (r *MsgStream) RecvClosed() <-chan struct{} {
+	return r.reader.RecvClosed()
+}
+
+// Flush writes any buffered messages to the remote.
+func (r *MsgStream) Flush() error {
+	if f, ok := r.writer.(Flusher); ok {
+		return r.handleWriteErr(f.Flush())
+	}
+	return nil
+}
+
+// IsRecvClosed checks if the remote has closed its send side.
+func (r *MsgStream) IsRecvClosed() bool {
+	return isChanClosed(r.reader.RecvClosed())
+}
+
+// IsSendClosed checks if CloseSend or Close was called.
+func (r *MsgStream) IsSendClosed() bool {
+	return atomic.LoadUint32(&r.sendClosed) == 1
+}
+
+// MsgSendComplete sends the message and closes the send side in one packet.
+func (r *MsgStream) MsgSendComplete(msg Message) error {
+	if err := r.getWriteErr(); err != nil {
+		return err
+	}
 	select {
-	case <-r.Context().Done():
+	case <-r.ctx.Done():
 		return context.Canceled
-	case data, ok := <-r.dataCh:
-		if !ok {
-			return io.EOF
-		}
-		return msg.UnmarshalVT(data)
+	default:
+	}
+
+	msgData, err := marshalMessage(msg)
+	if err != nil {
+		return err
 	}
+	if !atomic.CompareAndSwapUint32(&r.sendClosed, 0, 1) {
+		return ErrCompleted
+	}
+	outPkt := NewCallDataPacket(msgData, len(msgData) == 0, true, nil)
+	return r.writeCompletePacket(outPkt)
 }
 
 // CloseSend signals to the remote that we will no longer send any messages.
+// Flushes any buffered messages before returning.
+// Does nothing if the send side is already closed.
 func (r *MsgStream) CloseSend() error {
+	if !atomic.CompareAndSwapUint32(&r.sendClosed, 0, 1) {
+		return nil
+	}
+	if err := r.getWriteErr(); err != nil {
+		return err
+	}
 	outPkt := NewCallDataPacket(nil, false, true, nil)
-	return r.writer.WritePacket(outPkt)
+	return r.writeCompletePacket(outPkt)
+}
+
+// Cancel signals to the remote to cancel the rpc and closes the send side.
+// Does nothing if the send side is already closed.
+func (r *MsgStream) Cancel() error {
+	if !atomic.CompareAndSwapUint32(&r.sendClosed, 0, 1) {
+		return nil
+	}
+	if err := r.getWriteErr(); err != nil {
+		return err
+	}
+	outPkt := NewCallDataPacket(nil, false, true, context.Canceled)
+	return r.writeCompletePacket(outPkt)
 }
 
 // Close closes the stream.
 func (r *MsgStream) Close() error {
+	atomic.StoreUint32(&r.sendClosed, 1)
 	_ = r.writer.Close()
+	r.ctxCancel()
 	return nil
 }
 
+// String returns a description of the stream for debugging.
+// Includes the description of the reader if it implements fmt.Stringer.
+func (r *MsgStream) String() string {
+	desc := "MsgStream"
+	if s, ok := r.reader.(fmt.Stringer); ok {
+		desc += " " + s.String()
+	}
+	return fmt.Sprintf("%s send_closed=%v", desc, r.IsSendClosed())
+}
+
+// writeCompletePacket writes a packet closing the send side and flushes it.
+//
+// The packet is flushed even with FlushBuffered, so the remote receives it
+// if the stream is dropped right after.
+func (r *MsgStream) writeCompletePacket(pkt *Packet) error {
+	if err := r.writer.WritePacket(pkt); err != nil {
+		return r.handleWriteErr(err)
+	}
+	return r.Flush()
+}
+
+// getWriteErr returns the first error returned by the writer, if any.
+func (r *MsgStream) getWriteErr() error {
+	r.writeErrMtx.Lock()
+	defer r.writeErrMtx.Unlock()
+	return r.writeErr
+}
+
+// handleWriteErr records the first error returned by the writer and cancels
+// the stream context. Returns the error wrapped in a TransportError.
+func (r *MsgStream) handleWriteErr(err error) error {
+	if err == nil {
+		return nil
+	}
+	r.writeErrMtx.Lock()
+	defer r.writeErrMtx.Unlock()
+	if r.writeErr == nil {
+		if !IsTransportError(err) {
+			err = &TransportError{Err: err}
+		}
+		r.writeErr = err
+		r.ctxCancel()
+	}
+	return r.writeErr
+}
+
 // _ is a type assertion
 var _ Stream = ((*MsgStream)(nil))
@@ -0,0 +1,276 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"io"
+	"testing"
+	"time"
+)
+
+// hugeMessage is a Message which reports a size over the max message size.
+type hugeMessage struct{}
+
+// SizeVT returns the encoded size of the message.
+func (hugeMessage) SizeVT() int { return int(maxMessageSize) + 1 }
+
+// MarshalVT encodes the message.
+func (hugeMessage) MarshalVT() ([]byte, error) { panic("unexpected encode of oversized message") }
+
+// UnmarshalVT decodes the message.
+func (hugeMessage) UnmarshalVT([]byte) error { return nil }
+
+func TestMsgStream_MessageTooLarge(t *testing.T) {
+	strm := NewMsgStream(context.Background(), discardWriter{}, nil)
+	if err := strm.MsgSend(hugeMessage{}); err != ErrMessageTooLarge {
+		t.Fatalf("expected %v got %v", ErrMessageTooLarge, err)
+	}
+	raw := make(RawMessage, int(maxMessageSize)+1)
+	if err := strm.MsgSend(&raw); err != ErrMessageTooLarge {
+		t.Fatalf("expected %v got %v", ErrMessageTooLarge, err)
+	}
+}
+
+func TestMsgStream_MessageSizeBoundary(t *testing.T) {
+	for _, n := range []int{0, 1, 127, 128} {
+		pkt := NewCallDataPacket(make([]byte, n), n == 0, true, nil)
+		if size := callDataPacketSize(n); size != pkt.SizeVT() {
+			t.Fatalf("expected packet size %d got %d for %d bytes", pkt.SizeVT(), size, n)
+		}
+	}
+
+	// find the largest message which fits in a CallData packet.
+	n := int(maxMessageSize)
+	for callDataPacketSize(n) > int(maxMessageSize) {
+		n--
+	}
+	strm := NewMsgStream(context.Background(), discardWriter{}, nil)
+	raw := make(RawMessage, n)
+	if err := strm.MsgSend(&raw); err != nil {
+		t.Fatal(err.Error())
+	}
+	if size := NewCallDataPacket(raw, false, true, nil).SizeVT(); size > int(maxMessageSize) {
+		t.Fatalf("packet size %d exceeds max %v", size, maxMessageSize)
+	}
+	raw = make(RawMessage, n+1)
+	if err := strm.MsgSend(&raw); err != ErrMessageTooLarge {
+		t.Fatalf("expected %v got %v", ErrMessageTooLarge, err)
+	}
+}
+
+func TestMsgStream_IsClosed(t *testing.T) {
+	ctx := context.Background()
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	writer := &recordWriter{}
+	if err := rpc.Start(writer, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	strm := NewMsgStream(ctx, writer, rpc)
+	if strm.IsSendClosed() || strm.IsRecvClosed() {
+		t.Fatal("expected the stream to be open")
+	}
+
+	// closing the send side twice writes a single packet.
+	for i := 0; i < 2; i++ {
+		if err := strm.CloseSend(); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	writer.assertPackets(t, []*Packet{NewCallStartPacket("test.Service", "Method", nil, false), interopComplete})
+	if !strm.IsSendClosed() || strm.IsRecvClosed() {
+		t.Fatal("expected only the send side to be closed")
+	}
+
+	if err := rpc.HandlePacket(interopComplete); err != nil {
+		t.Fatal(err.Error())
+	}
+	if !strm.IsRecvClosed() {
+		t.Fatal("expected the receive side to be closed")
+	}
+}
+
+func TestMsgStream_RecvErrors(t *testing.T) {
+	ctx := context.Background()
+	remoteErr := errors.New("remote failed")
+	newStream := func() (*ClientRPC, *MsgStream) {
+		rpc := NewClientRPC(ctx, "test.Service", "Method")
+		if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+			t.Fatal(err.Error())
+		}
+		return rpc, NewMsgStream(ctx, discardWriter{}, rpc)
+	}
+	var msg RawMessage
+
+	// the remote completed the rpc.
+	rpc, strm := newStream()
+	if err := rpc.HandlePacket(NewCallDataPacket(nil, false, true, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := strm.MsgRecv(&msg); err != io.EOF {
+		t.Fatalf("expected %v got %v", io.EOF, err)
+	}
+
+	// the remote failed the rpc.
+	rpc, strm = newStream()
+	if err := rpc.HandlePacket(NewCallDataPacket(nil, false, true, remoteErr)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := strm.MsgRecv(&msg); err == nil || IsTransportError(err) || err.Error() != remoteErr.Error() {
+		t.Fatalf("expected %v got %v", remoteErr, err)
+	}
+
+	// the transport closed before the rpc completed.
+	rpc, strm = newStream()
+	rpc.HandleStreamClose(nil)
+	if err := strm.MsgRecv(&msg); !IsTransportError(err) || !errors.Is(err, io.ErrUnexpectedEOF) {
+		t.Fatalf("expected transport error got %v", err)
+	}
+}
+
+// failWriter is a Writer which fails all writes.
+type failWriter struct {
+	discardWriter
+	err    error
+	writes int
+}
+
+// WritePacket writes a packet to the remote.
+func (w *failWriter) WritePacket(p *Packet) error {
+	w.writes++
+	return w.err
+}
+
+func TestMsgStream_WriteError(t *testing.T) {
+	writeErr := errors.New("broken pipe")
+	writer := &failWriter{err: writeErr}
+	strm := NewMsgStream(context.Background(), writer, nil)
+
+	msg := RawMessage("hello")
+	err := strm.MsgSend(&msg)
+	if !errors.Is(err, writeErr) || !IsTransportError(err) {
+		t.Fatalf("expected transport error got %v", err)
+	}
+	select {
+	case <-strm.Context().Done():
+	default:
+		t.Fatal("expected the stream context to be canceled")
+	}
+
+	// subsequent sends return the stored error without writing.
+	if err2 := strm.MsgSend(&msg); err2 != err {
+		t.Fatalf("expected %v got %v", err, err2)
+	}
+	if err2 := strm.CloseSend(); err2 != err {
+		t.Fatalf("expected %v got %v", err, err2)
+	}
+	if writer.writes != 1 {
+		t.Fatalf("expected 1 write got %d", writer.writes)
+	}
+}
+
+func TestMsgStream_String(t *testing.T) {
+	ctx := context.Background()
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	strm := NewMsgStream(ctx, discardWriter{}, rpc)
+	expected := "MsgStream ClientRPC(test.Service/Method recv_closed=false) send_closed=false"
+	if desc := strm.String(); desc != expected {
+		t.Fatalf("expected %q got %q", expected, desc)
+	}
+	if err := rpc.HandlePacket(interopComplete); err != nil {
+		t.Fatal(err.Error())
+	}
+	_ = strm.CloseSend()
+	expected = "MsgStream ClientRPC(test.Service/Method recv_closed=true) send_closed=true"
+	if desc := strm.String(); desc != expected {
+		t.Fatalf("expected %q got %q", expected, desc)
+	}
+}
+
+func TestMsgStream_Cancel(t *testing.T) {
+	ctx := context.Background()
+	writer := &recordWriter{}
+	strm := NewMsgStream(ctx, writer, nil)
+	for i := 0; i < 2; i++ {
+		if err := strm.Cancel(); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if err := strm.CloseSend(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if !strm.IsSendClosed() || strm.Context().Err() != nil {
+		t.Fatal("expected the send side to be closed and the stream to be open")
+	}
+	writer.assertPackets(t, []*Packet{NewCallDataPacket(nil, false, true, context.Canceled)})
+}
+
+func TestMsgStream_MsgSendComplete(t *testing.T) {
+	ctx := context.Background()
+	writer := &recordWriter{}
+	strm := NewMsgStream(ctx, writer, nil)
+	msg := RawMessage("hello")
+	if err := MsgSendComplete(strm, &msg); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := MsgSendComplete(strm, &msg); err != ErrCompleted {
+		t.Fatalf("expected completed got %v", err)
+	}
+	if err := strm.CloseSend(); err != nil {
+		t.Fatal(err.Error())
+	}
+	writer.assertPackets(t, []*Packet{NewCallDataPacket([]byte("hello"), false, true, nil)})
+}
+
+func TestMsgStream_CloseSendFlush(t *testing.T) {
+	ctx := context.Background()
+	rwc := &notifyRWC{writeCh: make(chan struct{}, 10)}
+	prw := NewPacketReadWriter(rwc)
+	prw.SetFlushPolicy(FlushBuffered, time.Hour)
+	strm := NewMsgStream(ctx, prw, nil)
+	msg := RawMessage("hello")
+	if err := strm.MsgSend(&msg); err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(rwc.writeCh) != 0 {
+		t.Fatal("expected the message to be buffered")
+	}
+	if err := strm.CloseSend(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(rwc.writeCh) == 0 {
+		t.Fatal("expected the completion to be flushed")
+	}
+
+	var pkts []*Packet
+	err := NewPacketReadWriter(&rwc.bufferRWC).ReadToHandler(func(pkt *Packet) error {
+		pkts = append(pkts, pkt)
+		return nil
+	})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(pkts) != 2 || !pkts[1].GetCallData().GetComplete() {
+		t.Fatalf("expected the message and completion got %v", pkts)
+	}
+}
+
+func TestMsgStream_CloseUnblocksRecv(t *testing.T) {
+	ctx := context.Background()
+	rpc := NewClientRPC(ctx, "test.Service", "Method")
+	if err := rpc.Start(discardWriter{}, false, nil); err != nil {
+		t.Fatal(err.Error())
+	}
+	strm := NewMsgStream(ctx, discardWriter{}, rpc)
+	errCh := make(chan error, 1)
+	go func() {
+		var msg RawMessage
+		errCh <- strm.MsgRecv(&msg)
+	}()
+	_ = strm.Close()
+	if err := <-errCh; err != context.Canceled {
+		t.Fatalf("expected %v got %v", context.Canceled, err)
+	}
+}
@@ -1,6 +1,10 @@
 package srpc
 
-import "sync"
+import (
+	"sort"
+	"strings"
+	"sync"
+)
 
 // Mux contains a set of <service, method> handlers.
 type Mux interface {
@@ -8,10 +12,42 @@ type Mux interface {
 	Register(handler Handler) error
 	// InvokeMethod invokes the method matching the service & method ID.
 	// Returns false, nil if not found.
-	// If service string is empty, ignore it.
+	// If service string is empty, ignore it: the first registered service
+	// with the method is used.
 	InvokeMethod(serviceID, methodID string, strm Stream) (bool, error)
 }
 
+// Invoker invokes rpc methods.
+type Invoker interface {
+	// InvokeMethod invokes the method matching the service & method ID.
+	// Returns false, nil if not found.
+	InvokeMethod(serviceID, methodID string, strm Stream) (bool, error)
+}
+
+// PrefixRegistrar is a Mux which can route service ID prefixes to an invoker.
+type PrefixRegistrar interface {
+	// RegisterPrefix registers an invoker for all services with the prefix.
+	//
+	// Handlers registered with Register take precedence. Otherwise the invoker
+	// with the longest matching prefix is used.
+	RegisterPrefix(prefix string, invoker Invoker) error
+}
+
+// HandlerReplacer is a Mux which can replace all of its handlers atomically.
+type HandlerReplacer interface {
+	// ReplaceAll atomically replaces all registered handlers.
+	//
+	// Concurrent calls see either the old or the new set of handlers.
+	// Invokers registered with RegisterPrefix are kept.
+	ReplaceAll(handlers ...Handler) error
+}
+
+// QueryableInvoker can be queried for the methods it handles.
+type QueryableInvoker interface {
+	// HasServiceMethod checks if the <service, method> pair has a handler.
+	HasServiceMethod(serviceID, methodID string) bool
+}
+
 // muxMethods is a mapping from method id to handler.
 type muxMethods map[string]Handler
 
@@ -21,6 +57,18 @@ type mux struct {
 	rmtx sync.RWMutex
 	// services contains a mapping from services to handlers.
 	services map[string]muxMethods
+	// serviceOrder contains the service ids in registration order.
+	serviceOrder []string
+	// prefixes contains the prefix invokers sorted by prefix length descending.
+	prefixes []muxPrefix
+}
+
+// muxPrefix is an invoker registered for a service id prefix.
+type muxPrefix struct {
+	// prefix is the service id prefix
+	prefix string
+	// invoker is the invoker
+	invoker Invoker
 }
 
 // NewMux constructs a new Mux.
@@ -30,47 +78,142 @@ func NewMux() Mux {
 
 // Register registers a new RPC method handler (service).
 func (m *mux) Register(handler Handler) error {
-	serviceID := handler.GetServiceID()
-	methodIDs := handler.GetMethodIDs()
-	if serviceID == "" {
+	if handler.GetServiceID() == "" {
 		return ErrEmptyServiceID
 	}
 
 	m.rmtx.Lock()
 	defer m.rmtx.Unlock()
+	m.serviceOrder = addMuxHandler(m.services, m.serviceOrder, handler)
+	return nil
+}
+
+// ReplaceAll atomically replaces all registered handlers.
+//
+// Concurrent calls see either the old or the new set of handlers.
+// Invokers registered with RegisterPrefix are kept.
+func (m *mux) ReplaceAll(handlers ...Handler) error {
+	services := make(map[string]muxMethods)
+	var serviceOrder []string
+	for _, handler := range handlers {
+		if handler.GetServiceID() == "" {
+			return ErrEmptyServiceID
+		}
+		serviceOrder = addMuxHandler(services, serviceOrder, handler)
+	}
+
+	m.rmtx.Lock()
+	m.services, m.serviceOrder = services, serviceOrder
+	m.rmtx.Unlock()
+	return nil
+}
 
-	serviceMethods := m.services[serviceID]
+// addMuxHandler adds the handler methods to the services map.
+// Returns the service order with the service appended if it was new.
+func addMuxHandler(services map[string]muxMethods, serviceOrder []string, handler Handler) []string {
+	serviceID := handler.GetServiceID()
+	serviceMethods := services[serviceID]
 	if serviceMethods == nil {
 		serviceMethods = make(muxMethods)
-		m.services[serviceID] = serviceMethods
+		services[serviceID] = serviceMethods
+		serviceOrder = append(serviceOrder, serviceID)
 	}
-	for _, methodID := range methodIDs {
+	for _, methodID := range handler.GetMethodIDs() {
 		if methodID != "" {
 			serviceMethods[methodID] = handler
 		}
 	}
+	return serviceOrder
+}
 
+// RegisterPrefix registers an invoker for all services with the prefix.
+//
+// Handlers registered with Register take precedence. Otherwise the invoker
+// with the longest matching prefix is used. Replaces any existing invoker
+// with the same prefix.
+func (m *mux) RegisterPrefix(prefix string, invoker Invoker) error {
+	if prefix == "" {
+		return ErrEmptyServiceID
+	}
+
+	m.rmtx.Lock()
+	defer m.rmtx.Unlock()
+
+	for i := range m.prefixes {
+		if m.prefixes[i].prefix == prefix {
+			m.prefixes[i].invoker = invoker
+			return nil
+		}
+	}
+	m.prefixes = append(m.prefixes, muxPrefix{prefix: prefix, invoker: invoker})
+	sort.SliceStable(m.prefixes, func(i, j int) bool {
+		return len(m.prefixes[i].prefix) > len(m.prefixes[j].prefix)
+	})
 	return nil
 }
 
+// HasServiceMethod checks if the <service, method> pair has a handler.
+//
+// For prefix invokers which are not a QueryableInvoker, returns true.
+func (m *mux) HasServiceMethod(serviceID, methodID string) bool {
+	handler, invoker := m.getHandler(serviceID, methodID)
+	if handler != nil {
+		return true
+	}
+	return invoker != nil && invokerHasServiceMethod(invoker, serviceID, methodID)
+}
+
 // InvokeMethod invokes the method matching the service & method ID.
 // Returns false, nil if not found.
-// If service string is empty, ignore it.
+// If service string is empty, ignore it: the first registered service with the
+// method is used.
 func (m *mux) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
-	var handler Handler
-	m.rmtx.RLock()
-	svcMethods := m.services[serviceID]
-	if svcMethods != nil {
-		handler = svcMethods[methodID]
-	}
-	m.rmtx.RUnlock()
-
+	handler, invoker := m.getHandler(serviceID, methodID)
 	if handler == nil {
+		if invoker != nil {
+			return invoker.InvokeMethod(serviceID, methodID, strm)
+		}
 		return false, nil
 	}
 
 	return handler.InvokeMethod(serviceID, methodID, strm)
 }
 
+// getHandler returns the handler for the <service, method> pair, if any.
+// Otherwise returns the prefix invoker for the service, if any.
+//
+// If serviceID is empty, returns the handler of the first registered service
+// with the method.
+func (m *mux) getHandler(serviceID, methodID string) (Handler, Invoker) {
+	m.rmtx.RLock()
+	defer m.rmtx.RUnlock()
+	if serviceID == "" {
+		// search the services in registration order.
+		for _, svcID := range m.serviceOrder {
+			if handler := m.services[svcID][methodID]; handler != nil {
+				return handler, nil
+			}
+		}
+		return nil, nil
+	}
+	svcMethods := m.services[serviceID]
+	if svcMethods != nil {
+		if handler := svcMethods[methodID]; handler != nil {
+			return handler, nil
+		}
+	}
+	for _, pf := range m.prefixes {
+		if strings.HasPrefix(serviceID, pf.prefix) {
+			return nil, pf.invoker
+		}
+	}
+	return nil, nil
+}
+
 // _ is a type assertion
-var _ Mux = ((*mux)(nil))
+var (
+	_ Mux              = ((*mux)(nil))
+	_ PrefixRegistrar  = ((*mux)(nil))
+	_ HandlerReplacer  = ((*mux)(nil))
+	_ QueryableInvoker = ((*mux)(nil))
+)
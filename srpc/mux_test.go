@@ -0,0 +1,121 @@
+package srpc
+
+import (
+	"errors"
+	"testing"
+)
+
+// namedInvoker is an Invoker which returns an error with its name.
+type namedInvoker struct {
+	name string
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+func (i *namedInvoker) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	return true, errors.New(i.name)
+}
+
+func TestMux_RegisterPrefix(t *testing.T) {
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(errors.New("exact"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	registrar := mux.(PrefixRegistrar)
+	if err := registrar.RegisterPrefix("test.", &namedInvoker{name: "short"}); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := registrar.RegisterPrefix("test.Plugin", &namedInvoker{name: "long"}); err != nil {
+		t.Fatal(err.Error())
+	}
+
+	cases := []struct {
+		serviceID, methodID string
+		expected            string
+	}{
+		// exact handlers take precedence.
+		{"test.Service", "Method", "exact"},
+		// unmatched methods of a registered service fall back to the prefix.
+		{"test.Service", "Other", "short"},
+		// the longest prefix is used.
+		{"test.PluginA", "Method", "long"},
+		{"test.Other", "Method", "short"},
+	}
+	for _, tc := range cases {
+		ok, err := mux.InvokeMethod(tc.serviceID, tc.methodID, nil)
+		if !ok || err == nil || err.Error() != tc.expected {
+			t.Fatalf("%s/%s: expected %s got %v, %v", tc.serviceID, tc.methodID, tc.expected, ok, err)
+		}
+	}
+
+	if ok, err := mux.InvokeMethod("other.Service", "Method", nil); ok || err != nil {
+		t.Fatalf("expected not found got %v, %v", ok, err)
+	}
+}
+
+func TestMux_ReplaceAll(t *testing.T) {
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(errors.New("old"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := mux.(PrefixRegistrar).RegisterPrefix("prefix.", &namedInvoker{name: "prefix"}); err != nil {
+		t.Fatal(err.Error())
+	}
+
+	// invalid handlers leave the existing handlers in place.
+	replacer := mux.(HandlerReplacer)
+	if err := replacer.ReplaceAll(interopHandler{}, &emptyServiceHandler{}); err != ErrEmptyServiceID {
+		t.Fatalf("expected %v got %v", ErrEmptyServiceID, err)
+	}
+	if _, err := mux.InvokeMethod("test.Service", "Method", nil); err == nil || err.Error() != "old" {
+		t.Fatalf("expected old handler got %v", err)
+	}
+
+	if err := replacer.ReplaceAll(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	if ok, _ := mux.InvokeMethod("test.Service", "Method", nil); ok {
+		t.Fatal("expected the old service to be removed")
+	}
+	q := mux.(QueryableInvoker)
+	if !q.HasServiceMethod(interopService, "Unary") {
+		t.Fatal("expected the new service to be registered")
+	}
+	if _, err := mux.InvokeMethod("prefix.Service", "Method", nil); err == nil || err.Error() != "prefix" {
+		t.Fatalf("expected the prefix invoker to be kept got %v", err)
+	}
+}
+
+// emptyServiceHandler is a Handler with an empty service ID.
+type emptyServiceHandler struct{}
+
+// GetServiceID returns the ID of the service.
+func (emptyServiceHandler) GetServiceID() string { return "" }
+
+// GetMethodIDs returns the list of methods for the service.
+func (emptyServiceHandler) GetMethodIDs() []string { return nil }
+
+// InvokeMethod invokes the method matching the service & method ID.
+func (emptyServiceHandler) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	return false, nil
+}
+
+func TestMux_EmptyServiceID(t *testing.T) {
+	mux := NewMux()
+	for _, id := range []string{"test.B", "test.A", "test.C"} {
+		handler := newErrHandler(errors.New(id))
+		handler.serviceID = id
+		if err := mux.Register(handler); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	// the first registered service with the method is used.
+	for i := 0; i < 10; i++ {
+		ok, err := mux.InvokeMethod("", "Method", nil)
+		if !ok || err == nil || err.Error() != "test.B" {
+			t.Fatalf("expected test.B got %v: %v", ok, err)
+		}
+	}
+	if ok, _ := mux.InvokeMethod("", "Other", nil); ok {
+		t.Fatal("expected no handler for Other")
+	}
+}
@@ -0,0 +1,153 @@
+package srpc
+
+import (
+	"context"
+	"io"
+	"sync"
+)
+
+// Observer observes packets and rpcs, for example to collect metrics.
+//
+// Methods may be called concurrently and should not block.
+type Observer interface {
+	// OnPacketSent is called after a packet was written to the remote.
+	// size is the encoded size of the packet excluding the length prefix.
+	OnPacketSent(pkt *Packet, size int)
+	// OnPacketReceived is called before an incoming packet is handled.
+	// size is the encoded size of the packet excluding the length prefix.
+	OnPacketReceived(pkt *Packet, size int)
+	// OnRPCStart is called when a CallStart is sent or received.
+	OnRPCStart(service, method string)
+	// OnRPCEnd is called when a started rpc ends with an optional error.
+	OnRPCEnd(service, method string, err error)
+}
+
+// NewObserverOpenStream wraps an OpenStreamFunc to call an Observer.
+func NewObserverOpenStream(openStream OpenStreamFunc, obs Observer) OpenStreamFunc {
+	return func(ctx context.Context, msgHandler PacketHandler, closeHandler CloseHandler) (Writer, error) {
+		strm := &observedStream{obs: obs}
+		writer, err := openStream(ctx, strm.wrapPacketHandler(msgHandler), strm.wrapCloseHandler(closeHandler))
+		if err != nil {
+			return nil, err
+		}
+		strm.writer = writer
+		return strm, nil
+	}
+}
+
+// observedStream calls an Observer for the packets of a single stream.
+type observedStream struct {
+	// obs is the observer
+	obs Observer
+	// writer is the underlying writer
+	writer Writer
+
+	// mtx guards below fields
+	mtx sync.Mutex
+	// service is the rpc service, set by CallStart
+	service string
+	// method is the rpc method, set by CallStart
+	method string
+	// rpcErr is the first error sent or received in a CallData
+	rpcErr error
+	// ended indicates OnRPCEnd was called
+	ended bool
+}
+
+// newObservedStream constructs a new observedStream with a writer.
+func newObservedStream(obs Observer, writer Writer) *observedStream {
+	return &observedStream{obs: obs, writer: writer}
+}
+
+// WritePacket writes a packet to the remote.
+func (o *observedStream) WritePacket(p *Packet) error {
+	if err := o.writer.WritePacket(p); err != nil {
+		return err
+	}
+	o.observePacket(p)
+	o.obs.OnPacketSent(p, p.SizeVT())
+	return nil
+}
+
+// Flush writes any buffered packets to the remote.
+func (o *observedStream) Flush() error {
+	if f, ok := o.writer.(Flusher); ok {
+		return f.Flush()
+	}
+	return nil
+}
+
+// Close closes the writer.
+func (o *observedStream) Close() error {
+	err := o.writer.Close()
+	o.end(nil)
+	return err
+}
+
+// wrapPacketHandler wraps a PacketHandler to observe incoming packets.
+func (o *observedStream) wrapPacketHandler(cb PacketHandler) PacketHandler {
+	return func(pkt *Packet) error {
+		if pkt != nil {
+			o.observePacket(pkt)
+			o.obs.OnPacketReceived(pkt, pkt.SizeVT())
+		}
+		return cb(pkt)
+	}
+}
+
+// wrapCloseHandler wraps a CloseHandler to observe the stream closing.
+func (o *observedStream) wrapCloseHandler(cb CloseHandler) CloseHandler {
+	return func(closeErr error) {
+		o.end(closeErr)
+		if cb != nil {
+			cb(closeErr)
+		}
+	}
+}
+
+// observePacket records the rpc start and any rpc error from a packet.
+func (o *observedStream) observePacket(pkt *Packet) {
+	switch b := pkt.GetBody().(type) {
+	case *Packet_CallStart:
+		o.mtx.Lock()
+		started := o.service != "" || o.method != ""
+		if !started {
+			o.service, o.method = b.CallStart.GetRpcService(), b.CallStart.GetRpcMethod()
+		}
+		o.mtx.Unlock()
+		if !started {
+			o.obs.OnRPCStart(b.CallStart.GetRpcService(), b.CallStart.GetRpcMethod())
+		}
+	case *Packet_CallData:
+		if errStr := b.CallData.GetError(); errStr != "" {
+			o.mtx.Lock()
+			if o.rpcErr == nil {
+				o.rpcErr = ErrorFromString(errStr)
+			}
+			o.mtx.Unlock()
+		}
+	}
+}
+
+// end calls OnRPCEnd once if the rpc was started.
+func (o *observedStream) end(closeErr error) {
+	o.mtx.Lock()
+	if o.ended || (o.service == "" && o.method == "") {
+		o.mtx.Unlock()
+		return
+	}
+	o.ended = true
+	err := o.rpcErr
+	if err == nil && closeErr != io.EOF {
+		err = closeErr
+	}
+	service, method := o.service, o.method
+	o.mtx.Unlock()
+	o.obs.OnRPCEnd(service, method, err)
+}
+
+// _ is a type assertion
+var (
+	_ Writer  = ((*observedStream)(nil))
+	_ Flusher = ((*observedStream)(nil))
+)
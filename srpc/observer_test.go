@@ -0,0 +1,121 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"sync"
+	"testing"
+	"time"
+)
+
+// recordObserver is an Observer which records the observed packets and rpcs.
+type recordObserver struct {
+	// mtx guards below fields
+	mtx sync.Mutex
+	// sent and received count the packets
+	sent, received int
+	// sentSize and receivedSize are the total size of the packets
+	sentSize, receivedSize int
+	// started contains the started rpcs
+	started []string
+	// ended contains the ended rpcs
+	ended []string
+	// endErr is the error passed to the last OnRPCEnd
+	endErr error
+	// endCh is closed when OnRPCEnd is called
+	endCh chan struct{}
+}
+
+// newRecordObserver constructs a new recordObserver.
+func newRecordObserver() *recordObserver {
+	return &recordObserver{endCh: make(chan struct{})}
+}
+
+// OnPacketSent is called after a packet was written to the remote.
+func (o *recordObserver) OnPacketSent(pkt *Packet, size int) {
+	o.mtx.Lock()
+	o.sent++
+	o.sentSize += size
+	o.mtx.Unlock()
+}
+
+// OnPacketReceived is called before an incoming packet is handled.
+func (o *recordObserver) OnPacketReceived(pkt *Packet, size int) {
+	o.mtx.Lock()
+	o.received++
+	o.receivedSize += size
+	o.mtx.Unlock()
+}
+
+// OnRPCStart is called when a CallStart is sent or received.
+func (o *recordObserver) OnRPCStart(service, method string) {
+	o.mtx.Lock()
+	o.started = append(o.started, service+"/"+method)
+	o.mtx.Unlock()
+}
+
+// OnRPCEnd is called when a started rpc ends with an optional error.
+func (o *recordObserver) OnRPCEnd(service, method string, err error) {
+	o.mtx.Lock()
+	o.ended = append(o.ended, service+"/"+method)
+	o.endErr = err
+	o.mtx.Unlock()
+	close(o.endCh)
+}
+
+// wait waits for OnRPCEnd to be called.
+func (o *recordObserver) wait(t *testing.T) {
+	select {
+	case <-o.endCh:
+	case <-time.After(time.Second):
+		t.Fatal("timeout waiting for OnRPCEnd")
+	}
+}
+
+func TestObserver(t *testing.T) {
+	handlerErr := errors.New("handler failed")
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(handlerErr)); err != nil {
+		t.Fatal(err.Error())
+	}
+	serverObs, clientObs := newRecordObserver(), newRecordObserver()
+	server := NewServer(mux, WithObserver(serverObs))
+	client := NewClient(NewObserverOpenStream(NewServerPipe(server), clientObs))
+
+	in, out := RawMessage("hello"), new(RawMessage)
+	err := client.Invoke(context.Background(), "test.Service", "Method", &in, out)
+	if err == nil || err.Error() != handlerErr.Error() {
+		t.Fatalf("expected %v got %v", handlerErr, err)
+	}
+	serverObs.wait(t)
+	clientObs.wait(t)
+
+	// the client sends the CallStart and the server responds with the error.
+	startSize := NewCallStartPacket("test.Service", "Method", in, false).SizeVT()
+	errSize := NewCallDataPacket(nil, false, true, handlerErr).SizeVT()
+	for _, obs := range []*recordObserver{clientObs, serverObs} {
+		obs.mtx.Lock()
+		if len(obs.started) != 1 || obs.started[0] != "test.Service/Method" {
+			t.Fatalf("unexpected started rpcs: %v", obs.started)
+		}
+		if len(obs.ended) != 1 || obs.ended[0] != "test.Service/Method" {
+			t.Fatalf("unexpected ended rpcs: %v", obs.ended)
+		}
+		if obs.endErr == nil || obs.endErr.Error() != handlerErr.Error() {
+			t.Fatalf("expected end error %v got %v", handlerErr, obs.endErr)
+		}
+		obs.mtx.Unlock()
+	}
+	if clientObs.sent != 1 || clientObs.sentSize != startSize {
+		t.Fatalf("client sent %d packets (%d bytes), expected 1 (%d bytes)", clientObs.sent, clientObs.sentSize, startSize)
+	}
+	if serverObs.received != 1 || serverObs.receivedSize != startSize {
+		t.Fatalf("server received %d packets (%d bytes), expected 1 (%d bytes)", serverObs.received, serverObs.receivedSize, startSize)
+	}
+	if serverObs.sent != 1 || serverObs.sentSize != errSize {
+		t.Fatalf("server sent %d packets (%d bytes), expected 1 (%d bytes)", serverObs.sent, serverObs.sentSize, errSize)
+	}
+	if clientObs.received != 1 || clientObs.receivedSize != errSize {
+		t.Fatalf("client received %d packets (%d bytes), expected 1 (%d bytes)", clientObs.received, clientObs.receivedSize, errSize)
+	}
+}
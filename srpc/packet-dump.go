@@ -0,0 +1,79 @@
+package srpc
+
+import (
+	"encoding/binary"
+	"encoding/hex"
+	"fmt"
+	"strings"
+)
+
+// DumpPacket formats a packet for debugging.
+//
+// Includes the body type, the lengths and flags, and a hex dump of the packet
+// framed with the length prefix as written by PacketReaderWriter.
+func DumpPacket(p *Packet) string {
+	var sb strings.Builder
+	switch b := p.GetBody().(type) {
+	case *Packet_CallStart:
+		fmt.Fprintf(
+			&sb,
+			"CallStart service=%q method=%q data_len=%d data_is_zero=%v\n",
+			b.CallStart.GetRpcService(),
+			b.CallStart.GetRpcMethod(),
+			len(b.CallStart.GetData()),
+			b.CallStart.GetDataIsZero(),
+		)
+	case *Packet_CallData:
+		fmt.Fprintf(
+			&sb,
+			"CallData data_len=%d data_is_zero=%v complete=%v error=%q\n",
+			len(b.CallData.GetData()),
+			b.CallData.GetDataIsZero(),
+			b.CallData.GetComplete(),
+			b.CallData.GetError(),
+		)
+	default:
+		sb.WriteString("Unknown packet body\n")
+	}
+
+	msgSize := p.SizeVT()
+	framed := make([]byte, 4+msgSize)
+	binary.LittleEndian.PutUint32(framed, uint32(msgSize))
+	if _, err := p.MarshalToVT(framed[4:]); err != nil {
+		fmt.Fprintf(&sb, "encode error: %v\n", err)
+		return sb.String()
+	}
+	fmt.Fprintf(&sb, "framed %d bytes:\n", len(framed))
+	sb.WriteString(hex.Dump(framed))
+	return sb.String()
+}
+
+// DumpFramedPackets formats a buffer of length-prefixed packets for debugging.
+//
+// Dumps each packet with DumpPacket. Stops at the first incomplete or
+// undecodable packet and dumps the remaining bytes.
+func DumpFramedPackets(data []byte) string {
+	var sb strings.Builder
+	for len(data) != 0 {
+		if len(data) < 4 {
+			fmt.Fprintf(&sb, "incomplete length prefix: %d bytes:\n", len(data))
+			sb.WriteString(hex.Dump(data))
+			break
+		}
+		msgSize := binary.LittleEndian.Uint32(data)
+		if uint64(len(data)-4) < uint64(msgSize) {
+			fmt.Fprintf(&sb, "incomplete packet: expected %d bytes got %d:\n", msgSize, len(data)-4)
+			sb.WriteString(hex.Dump(data))
+			break
+		}
+		pkt := &Packet{}
+		if err := pkt.UnmarshalVT(data[4 : 4+msgSize]); err != nil {
+			fmt.Fprintf(&sb, "decode error: %v:\n", err)
+			sb.WriteString(hex.Dump(data))
+			break
+		}
+		sb.WriteString(DumpPacket(pkt))
+		data = data[4+msgSize:]
+	}
+	return sb.String()
+}
@@ -0,0 +1,30 @@
+package srpc
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestDumpFramedPackets(t *testing.T) {
+	rwc := &bufferRWC{}
+	prw := NewPacketReadWriter(rwc)
+	if err := prw.WritePacket(NewCallStartPacket("test.Service", "Method", nil, true)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := prw.WritePacket(NewCallDataPacket(nil, false, true, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	// truncated length prefix
+	_, _ = rwc.Write([]byte{1, 0})
+
+	out := DumpFramedPackets(rwc.Bytes())
+	for _, snippet := range []string{
+		`CallStart service="test.Service" method="Method" data_len=0 data_is_zero=true`,
+		`CallData data_len=0 data_is_zero=false complete=true error=""`,
+		"incomplete length prefix: 2 bytes",
+	} {
+		if !strings.Contains(out, snippet) {
+			t.Fatalf("expected dump to contain %q:\n%s", snippet, out)
+		}
+	}
+}
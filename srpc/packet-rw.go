@@ -1,10 +1,14 @@
 package srpc
 
 import (
+	"bufio"
 	"bytes"
 	"context"
 	"encoding/binary"
 	"io"
+	"sync"
+	"sync/atomic"
+	"time"
 
 	"github.com/pkg/errors"
 )
@@ -12,6 +16,44 @@ import (
 // maxMessageSize is the max message size in bytes
 var maxMessageSize = 1e7
 
+// maxRetainedWriteBufSize is the max size of the encode buffer to keep between
+// writes in bytes.
+const maxRetainedWriteBufSize = 64 * 1024
+
+// DecodeErrorPolicy controls how the read pump handles a malformed packet.
+type DecodeErrorPolicy int
+
+const (
+	// DecodeErrorClose closes the stream on a malformed packet.
+	// This is the default.
+	DecodeErrorClose DecodeErrorPolicy = iota
+	// DecodeErrorSkip skips malformed packets and continues reading.
+	//
+	// Applies to packets with a valid length prefix which fail to decode or
+	// fail validation. An invalid length prefix cannot be resynchronized and
+	// always closes the stream.
+	DecodeErrorSkip
+)
+
+// FlushPolicy controls when written packets are flushed to the transport.
+type FlushPolicy int
+
+const (
+	// FlushImmediate writes each packet to the transport immediately.
+	// This is the default.
+	FlushImmediate FlushPolicy = iota
+	// FlushBuffered buffers written packets until Flush is called, the batch
+	// size is reached, or the flush delay elapsed since the first buffered
+	// packet.
+	FlushBuffered
+)
+
+// DefaultFlushDelay is the default max delay before flushing buffered packets.
+const DefaultFlushDelay = time.Millisecond
+
+// DefaultFlushBatchSize is the default max size of buffered packets in bytes.
+const DefaultFlushBatchSize = 4096
+
 // PacketReaderWriter reads and writes packets from a io.ReadWriter.
 // Uses a LittleEndian uint32 length prefix.
 type PacketReaderWriter struct {
@@ -19,6 +61,26 @@ type PacketReaderWriter struct {
 	rw io.ReadWriteCloser
 	// buf is the buffered data
 	buf bytes.Buffer
+	// writeMtx guards below write fields
+	writeMtx sync.Mutex
+	// writeBuf is the reused encode buffer
+	writeBuf []byte
+	// bw is the write buffer, if FlushBuffered
+	bw *bufio.Writer
+	// flushDelay is the max delay before flushing bw
+	flushDelay time.Duration
+	// flushBatchSize is the size of bw, if > 0
+	flushBatchSize int
+	// flushTimer flushes bw after flushDelay
+	flushTimer *time.Timer
+	// flushPending indicates flushTimer is running
+	flushPending bool
+	// decodeErrPolicy is the policy for malformed packets.
+	decodeErrPolicy DecodeErrorPolicy
+	// onDecodeErr is called with skipped packet errors, if set.
+	onDecodeErr func(err error)
+	// closedLocally is set to 1 when Close is called.
+	closedLocally uint32
 }
 
 // NewPacketReadWriter constructs a new read/writer.
@@ -26,19 +88,105 @@ func NewPacketReadWriter(rw io.ReadWriteCloser) *PacketReaderWriter {
 	return &PacketReaderWriter{rw: rw}
 }
 
+// SetDecodeErrorPolicy sets the policy for malformed incoming packets.
+//
+// onErr is optional, called with the error for each skipped packet.
+// Must be called before starting the read pump.
+func (r *PacketReaderWriter) SetDecodeErrorPolicy(policy DecodeErrorPolicy, onErr func(err error)) {
+	r.decodeErrPolicy, r.onDecodeErr = policy, onErr
+}
+
+// SetFlushPolicy sets the policy for flushing written packets.
+//
+// flushDelay is the max delay for FlushBuffered from the first buffered packet
+// until the flush, if <= 0 uses DefaultFlushDelay. Should be called before
+// writing any packets: any buffered packets are flushed first. If the flush
+// fails the policy is not changed and the error is returned by the next write.
+func (r *PacketReaderWriter) SetFlushPolicy(policy FlushPolicy, flushDelay time.Duration) {
+	r.writeMtx.Lock()
+	defer r.writeMtx.Unlock()
+	if err := r.flushLocked(); err != nil {
+		return
+	}
+	if policy != FlushBuffered {
+		r.bw = nil
+		return
+	}
+	if flushDelay <= 0 {
+		flushDelay = DefaultFlushDelay
+	}
+	r.bw, r.flushDelay = r.newFlushWriter(), flushDelay
+}
+
+// SetFlushBatchSize sets the max size in bytes of packets buffered by
+// FlushBuffered before flushing, if <= 0 uses DefaultFlushBatchSize.
+// Should be called before writing any packets: any buffered packets are
+// flushed first. If the flush fails the buffer is kept and the error is
+// returned by the next write.
+func (r *PacketReaderWriter) SetFlushBatchSize(size int) {
+	r.writeMtx.Lock()
+	defer r.writeMtx.Unlock()
+	r.flushBatchSize = size
+	if r.bw != nil && r.flushLocked() == nil {
+		r.bw = r.newFlushWriter()
+	}
+}
+
+// newFlushWriter constructs the write buffer for FlushBuffered.
+// Caller must hold writeMtx.
+func (r *PacketReaderWriter) newFlushWriter() *bufio.Writer {
+	size := r.flushBatchSize
+	if size <= 0 {
+		size = DefaultFlushBatchSize
+	}
+	return bufio.NewWriterSize(r.rw, size)
+}
+
 // WritePacket writes a packet to the writer.
+//
+// Reuses the encode buffer across calls.
 func (r *PacketReaderWriter) WritePacket(p *Packet) error {
+	r.writeMtx.Lock()
+	defer r.writeMtx.Unlock()
+
 	msgSize := p.SizeVT()
-	data := make([]byte, 4+msgSize)
+	if cap(r.writeBuf) < 4+msgSize {
+		r.writeBuf = make([]byte, 4+msgSize)
+	}
+	data := r.writeBuf[:4+msgSize]
+	// release large buffers after writing so they are not retained.
+	if cap(r.writeBuf) > maxRetainedWriteBufSize {
+		defer func() {
+			r.writeBuf = nil
+		}()
+	}
+
 	binary.LittleEndian.PutUint32(data, uint32(msgSize))
 	_, err := p.MarshalToVT(data[4:])
 	if err != nil {
 		return err
 	}
+	if r.bw != nil {
+		if _, err := r.bw.Write(data); err != nil {
+			return err
+		}
+		// start the flush deadline with the first buffered packet.
+		if r.bw.Buffered() != 0 && !r.flushPending {
+			r.flushPending = true
+			if r.flushTimer == nil {
+				r.flushTimer = time.AfterFunc(r.flushDelay, func() {
+					_ = r.Flush()
+				})
+			} else {
+				r.flushTimer.Reset(r.flushDelay)
+			}
+		}
+		return nil
+	}
 	var n int
 	written := 0
 	for written < len(data) {
-		n, err = r.rw.Write(data)
+		n, err = r.rw.Write(data[written:])
 		if err != nil {
 			return err
 		}
@@ -47,6 +195,26 @@ func (r *PacketReaderWriter) WritePacket(p *Packet) error {
 	return err
 }
 
+// Flush writes any buffered packets to the writer.
+func (r *PacketReaderWriter) Flush() error {
+	r.writeMtx.Lock()
+	defer r.writeMtx.Unlock()
+	return r.flushLocked()
+}
+
+// flushLocked writes any buffered packets to the writer.
+// Caller must hold writeMtx.
+func (r *PacketReaderWriter) flushLocked() error {
+	if r.bw == nil {
+		return nil
+	}
+	if r.flushPending {
+		r.flushTimer.Stop()
+		r.flushPending = false
+	}
+	return r.bw.Flush()
+}
+
 // ReadPump executes the read pump in a goroutine.
 //
 // calls the handler when closed or returning an error
@@ -60,16 +228,27 @@ func (r *PacketReaderWriter) ReadPump(cb PacketHandler, closed CloseHandler) {
 
 // ReadToHandler reads data to the given handler.
 // Does not handle closing the stream, use ReadPump instead.
+//
+// Returns nil if the remote closed the stream, context.Canceled if the stream
+// was closed locally, or the read or handler error otherwise.
 func (r *PacketReaderWriter) ReadToHandler(cb PacketHandler) error {
 	var currLen uint32
+	var closeErr error
 	buf := make([]byte, 2048)
 	isOpen := true
 	for isOpen {
 		// read some data into the buffer
 		n, err := r.rw.Read(buf)
 		if err != nil {
+			// the transport may return net.ErrClosed or a reset after Close.
+			if r.isClosedLocally() {
+				err = context.Canceled
+			}
 			if err == io.EOF || err == context.Canceled {
 				isOpen = false
+				if err == context.Canceled {
+					closeErr = err
+				}
 			} else {
 				return err
 			}
@@ -81,44 +260,98 @@ func (r *PacketReaderWriter) ReadToHandler(cb PacketHandler) error {
 			return err
 		}
 
-		// check if we have enough data for a length prefix
-		bufLen := r.buf.Len()
-		if bufLen < 4 {
-			continue
-		}
+		// emit all fully buffered packets
+		for {
+			// check if we have enough data for a length prefix
+			bufLen := r.buf.Len()
+			if bufLen < 4 {
+				break
+			}
 
-		// parse the length prefix if not done already
-		if currLen == 0 {
-			currLen = r.readLengthPrefix(r.buf.Bytes())
+			// parse the length prefix if not done already
 			if currLen == 0 {
-				return errors.New("unexpected zero len prefix")
+				currLen = r.readLengthPrefix(r.buf.Bytes())
+				if currLen == 0 {
+					return errors.New("unexpected zero len prefix")
+				}
+				if currLen > uint32(maxMessageSize) {
+					return errors.Errorf("message size %v greater than maximum %v", currLen, maxMessageSize)
+				}
 			}
-			if currLen > uint32(maxMessageSize) {
-				return errors.Errorf("message size %v greater than maximum %v", currLen, maxMessageSize)
+
+			// read large packets directly to avoid copying them through r.buf.
+			if bufLen < int(currLen)+4 && int(currLen) > len(buf) {
+				pkt := make([]byte, currLen)
+				nb := copy(pkt, r.buf.Next(bufLen)[4:])
+				currLen = 0
+				if _, err := io.ReadFull(r.rw, pkt[nb:]); err != nil {
+					if r.isClosedLocally() {
+						return context.Canceled
+					}
+					if err == io.EOF {
+						// the remote closed mid-packet.
+						return io.ErrUnexpectedEOF
+					}
+					return err
+				}
+				if err := r.handlePacketData(pkt, cb); err != nil {
+					return err
+				}
+				continue
+			}
+
+			// wait for more data if not fully buffered
+			if bufLen < int(currLen)+4 {
+				break
 			}
-		}
 
-		// emit the packet if fully buffered
-		if currLen != 0 && bufLen >= int(currLen)+4 {
 			pkt := r.buf.Next(int(currLen + 4))[4:]
 			currLen = 0
-			npkt := &Packet{}
-			if err := npkt.UnmarshalVT(pkt); err != nil {
-				return err
-			}
-			if err := cb(npkt); err != nil {
+			if err := r.handlePacketData(pkt, cb); err != nil {
 				return err
 			}
 		}
 	}
 
 	// closed
-	return nil
+	return closeErr
+}
+
+// handlePacketData decodes a framed packet and calls the handler.
+// Applies the decode error policy.
+func (r *PacketReaderWriter) handlePacketData(data []byte, cb PacketHandler) error {
+	npkt := &Packet{}
+	err := npkt.UnmarshalVT(data)
+	if err == nil && r.decodeErrPolicy == DecodeErrorSkip {
+		err = npkt.Validate()
+	}
+	if err != nil {
+		if r.decodeErrPolicy != DecodeErrorSkip {
+			return err
+		}
+		if r.onDecodeErr != nil {
+			r.onDecodeErr(err)
+		}
+		return nil
+	}
+	return cb(npkt)
 }
 
-// Close closes the packet rw.
+// Close flushes any buffered packets and closes the packet rw.
+//
+// The read pump returns context.Canceled after Close.
 func (r *PacketReaderWriter) Close() error {
-	return r.rw.Close()
+	atomic.StoreUint32(&r.closedLocally, 1)
+	flushErr := r.Flush()
+	if err := r.rw.Close(); err != nil {
+		return err
+	}
+	return flushErr
+}
+
+// isClosedLocally checks if Close was called.
+func (r *PacketReaderWriter) isClosedLocally() bool {
+	return atomic.LoadUint32(&r.closedLocally) == 1
 }
 
 // readLengthPrefix reads the length prefix.
@@ -130,4 +363,7 @@ func (r *PacketReaderWriter) readLengthPrefix(b []byte) uint32 {
 }
 
 // _ is a type assertion
-var _ Writer = (*PacketReaderWriter)(nil)
+var (
+	_ Writer  = (*PacketReaderWriter)(nil)
+	_ Flusher = (*PacketReaderWriter)(nil)
+)
@@ -0,0 +1,281 @@
+package srpc
+
+import (
+	"bytes"
+	"context"
+	"io"
+	"net"
+	"testing"
+	"time"
+)
+
+// bufferRWC is a ReadWriteCloser backed by a buffer.
+type bufferRWC struct {
+	bytes.Buffer
+}
+
+// Close closes the buffer.
+func (b *bufferRWC) Close() error { return nil }
+
+// buildMalformedStream builds a stream with an undecodable packet, an invalid
+// packet, and a valid packet.
+func buildMalformedStream(t *testing.T) *bufferRWC {
+	rwc := &bufferRWC{}
+	// length prefix 3 followed by a truncated varint
+	_, _ = rwc.Write([]byte{3, 0, 0, 0, 0xff, 0xff, 0xff})
+	prw := NewPacketReadWriter(rwc)
+	if err := prw.WritePacket(&Packet{Body: &Packet_CallData{CallData: &CallData{}}}); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := prw.WritePacket(NewCallDataPacket([]byte("hello"), false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	return rwc
+}
+
+func TestPacketReaderWriter_DecodeErrorClose(t *testing.T) {
+	prw := NewPacketReadWriter(buildMalformedStream(t))
+	var received int
+	err := prw.ReadToHandler(func(pkt *Packet) error {
+		received++
+		return pkt.Validate()
+	})
+	if err == nil {
+		t.Fatal("expected decode error")
+	}
+	if received != 0 {
+		t.Fatalf("expected no packets, got %d", received)
+	}
+}
+
+func TestPacketReaderWriter_DecodeErrorSkip(t *testing.T) {
+	prw := NewPacketReadWriter(buildMalformedStream(t))
+	var decodeErrs int
+	prw.SetDecodeErrorPolicy(DecodeErrorSkip, func(err error) {
+		decodeErrs++
+	})
+	var received [][]byte
+	err := prw.ReadToHandler(func(pkt *Packet) error {
+		received = append(received, pkt.GetCallData().GetData())
+		return nil
+	})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if decodeErrs != 2 {
+		t.Fatalf("expected 2 skipped packets, got %d", decodeErrs)
+	}
+	if len(received) != 1 || string(received[0]) != "hello" {
+		t.Fatalf("expected the valid packet, got %v", received)
+	}
+}
+
+func TestPacketReaderWriter_LargePacket(t *testing.T) {
+	rwc := &bufferRWC{}
+	prw := NewPacketReadWriter(rwc)
+	large := bytes.Repeat([]byte("a"), 1e5)
+	for _, data := range [][]byte{[]byte("before"), large, []byte("after")} {
+		if err := prw.WritePacket(NewCallDataPacket(data, false, false, nil)); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	var received [][]byte
+	err := prw.ReadToHandler(func(pkt *Packet) error {
+		received = append(received, pkt.GetCallData().GetData())
+		return nil
+	})
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(received) != 3 || string(received[0]) != "before" || !bytes.Equal(received[1], large) || string(received[2]) != "after" {
+		t.Fatal("expected the packets to be read in order")
+	}
+}
+
+func TestPacketReaderWriter_LargePacketTruncated(t *testing.T) {
+	rwc := &bufferRWC{}
+	prw := NewPacketReadWriter(rwc)
+	large := bytes.Repeat([]byte("a"), 1e5)
+	if err := prw.WritePacket(NewCallDataPacket(large, false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	data := append([]byte(nil), rwc.Bytes()...)
+
+	// cut mid-body, and exactly at the end of the first read.
+	for _, size := range []int{len(data) / 2, 2048} {
+		rwc.Reset()
+		_, _ = rwc.Write(data[:size])
+		err := prw.ReadToHandler(func(pkt *Packet) error {
+			t.Fatal("expected no packets")
+			return nil
+		})
+		if err != io.ErrUnexpectedEOF {
+			t.Fatalf("size %d: expected %v got %v", size, io.ErrUnexpectedEOF, err)
+		}
+	}
+}
+
+// benchmarkReadPackets benchmarks reading packets with the given data size.
+func benchmarkReadPackets(b *testing.B, size int) {
+	rwc := &bufferRWC{}
+	prw := NewPacketReadWriter(rwc)
+	pkt := NewCallDataPacket(bytes.Repeat([]byte("a"), size), false, false, nil)
+	b.ReportAllocs()
+	b.SetBytes(int64(size))
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		b.StopTimer()
+		if err := prw.WritePacket(pkt); err != nil {
+			b.Fatal(err.Error())
+		}
+		b.StartTimer()
+		if err := prw.ReadToHandler(func(pkt *Packet) error { return nil }); err != nil {
+			b.Fatal(err.Error())
+		}
+	}
+}
+
+func BenchmarkPacketReaderWriter_Read1KB(b *testing.B) {
+	benchmarkReadPackets(b, 1e3)
+}
+
+func BenchmarkPacketReaderWriter_Read1MB(b *testing.B) {
+	benchmarkReadPackets(b, 1e6)
+}
+
+// discardRWC is a ReadWriteCloser which discards all written data.
+type discardRWC struct {
+	bytes.Reader
+}
+
+// Write discards the data.
+func (d *discardRWC) Write(p []byte) (int, error) { return len(p), nil }
+
+// Close closes the stream.
+func (d *discardRWC) Close() error { return nil }
+
+func BenchmarkPacketReaderWriter_Write1KB(b *testing.B) {
+	prw := NewPacketReadWriter(&discardRWC{})
+	pkt := NewCallDataPacket(bytes.Repeat([]byte("a"), 1e3), false, false, nil)
+	b.ReportAllocs()
+	b.ResetTimer()
+	for i := 0; i < b.N; i++ {
+		if err := prw.WritePacket(pkt); err != nil {
+			b.Fatal(err.Error())
+		}
+	}
+}
+
+// notifyRWC is a bufferRWC which signals after each write.
+type notifyRWC struct {
+	bufferRWC
+	writeCh chan struct{}
+}
+
+// Write writes the data to the buffer and signals writeCh.
+func (n *notifyRWC) Write(p []byte) (int, error) {
+	written, err := n.bufferRWC.Write(p)
+	n.writeCh <- struct{}{}
+	return written, err
+}
+
+func TestPacketReaderWriter_FlushBuffered(t *testing.T) {
+	rwc := &notifyRWC{writeCh: make(chan struct{}, 10)}
+	prw := NewPacketReadWriter(rwc)
+	prw.SetFlushPolicy(FlushBuffered, time.Hour)
+	if err := prw.WritePacket(NewCallDataPacket([]byte("hello"), false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(rwc.writeCh) != 0 {
+		t.Fatal("expected the packet to be buffered")
+	}
+	if err := prw.Flush(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(rwc.writeCh) != 1 {
+		t.Fatal("expected the packet to be flushed")
+	}
+
+	// switching the policy flushes the buffered packets.
+	if err := prw.WritePacket(NewCallDataPacket([]byte("hello"), false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	prw.SetFlushPolicy(FlushImmediate, 0)
+	if len(rwc.writeCh) != 2 {
+		t.Fatal("expected the packet to be flushed when switching the policy")
+	}
+	<-rwc.writeCh
+	<-rwc.writeCh
+
+	// flushes when idle.
+	prw.SetFlushPolicy(FlushBuffered, time.Millisecond)
+	if err := prw.WritePacket(NewCallDataPacket([]byte("hello"), false, false, nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	<-rwc.writeCh
+	select {
+	case <-rwc.writeCh:
+	case <-time.After(time.Second):
+		t.Fatal("expected the packet to be flushed when idle")
+	}
+}
+
+func TestPacketReaderWriter_FlushDeadline(t *testing.T) {
+	rwc := &notifyRWC{writeCh: make(chan struct{}, 100)}
+	prw := NewPacketReadWriter(rwc)
+	prw.SetFlushPolicy(FlushBuffered, 50*time.Millisecond)
+
+	// the delay is measured from the first buffered packet: continuous writes
+	// do not postpone the flush.
+	pkt := NewCallDataPacket([]byte("hello"), false, false, nil)
+	for i := 0; i < 20; i++ {
+		if err := prw.WritePacket(pkt); err != nil {
+			t.Fatal(err.Error())
+		}
+		<-time.After(10 * time.Millisecond)
+	}
+	if len(rwc.writeCh) == 0 {
+		t.Fatal("expected the packets to be flushed during continuous writes")
+	}
+}
+
+func TestPacketReaderWriter_FlushBatchSize(t *testing.T) {
+	rwc := &notifyRWC{writeCh: make(chan struct{}, 10)}
+	prw := NewPacketReadWriter(rwc)
+	prw.SetFlushBatchSize(16)
+	prw.SetFlushPolicy(FlushBuffered, time.Hour)
+
+	// each framed packet is 13 bytes: the second packet fills the batch.
+	pkt := NewCallDataPacket([]byte("hello"), false, false, nil)
+	for i := 0; i < 2; i++ {
+		if err := prw.WritePacket(pkt); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if len(rwc.writeCh) != 1 {
+		t.Fatalf("expected 1 write got %d", len(rwc.writeCh))
+	}
+}
+
+func TestPacketReaderWriter_CloseLocal(t *testing.T) {
+	c1, c2 := net.Pipe()
+	defer c2.Close()
+	prw := NewPacketReadWriter(c1)
+	errCh := make(chan error, 1)
+	go func() {
+		errCh <- prw.ReadToHandler(func(pkt *Packet) error { return nil })
+	}()
+
+	// net.Pipe returns io.ErrClosedPipe after Close.
+	if err := prw.Close(); err != nil {
+		t.Fatal(err.Error())
+	}
+	select {
+	case err := <-errCh:
+		if err != context.Canceled {
+			t.Fatalf("expected %v got %v", context.Canceled, err)
+		}
+	case <-time.After(time.Second):
+		t.Fatal("timeout waiting for read pump to exit")
+	}
+}
@@ -0,0 +1,56 @@
+package srpc
+
+import (
+	"context"
+	"io"
+)
+
+// PacketTransport is a transport which carries whole packets for a single rpc.
+//
+// Implement to host rpcs over a message-oriented transport, for example a
+// message bus, without the length-prefixed framing of PacketReaderWriter.
+type PacketTransport interface {
+	// Writer writes packets to the remote.
+	Writer
+	// ReadPacket reads the next packet from the remote.
+	// Returns io.EOF when the remote closed the transport.
+	ReadPacket() (*Packet, error)
+}
+
+// ReadPacketTransport reads packets from the transport and calls the handler.
+//
+// Calls closed when the transport is closed or the handler returns an error.
+// The close error is nil if the remote closed the transport, context.Canceled
+// if it was closed locally, or the read or handler error otherwise.
+func ReadPacketTransport(t PacketTransport, cb PacketHandler, closed CloseHandler) {
+	var err error
+	for {
+		var pkt *Packet
+		pkt, err = t.ReadPacket()
+		if err == nil {
+			err = cb(pkt)
+		}
+		if err != nil {
+			break
+		}
+	}
+	if err == io.EOF {
+		err = nil
+	}
+	if closed != nil {
+		closed(err)
+	}
+}
+
+// NewPacketTransportOpenStream constructs an OpenStreamFunc which opens a
+// PacketTransport for each rpc.
+func NewPacketTransportOpenStream(open func(ctx context.Context) (PacketTransport, error)) OpenStreamFunc {
+	return func(ctx context.Context, msgHandler PacketHandler, closeHandler CloseHandler) (Writer, error) {
+		t, err := open(ctx)
+		if err != nil {
+			return nil, err
+		}
+		go ReadPacketTransport(t, msgHandler, closeHandler)
+		return t, nil
+	}
+}
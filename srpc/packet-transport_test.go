@@ -0,0 +1,72 @@
+package srpc
+
+import (
+	"context"
+	"io"
+	"sync"
+	"testing"
+)
+
+// chanPacketTransport is an in-memory PacketTransport.
+type chanPacketTransport struct {
+	// closeOnce guards closing out
+	closeOnce sync.Once
+	// in receives packets from the remote
+	in <-chan *Packet
+	// out sends packets to the remote
+	out chan<- *Packet
+}
+
+// newChanPacketTransportPair constructs a connected pair of transports.
+func newChanPacketTransportPair() (*chanPacketTransport, *chanPacketTransport) {
+	ch1, ch2 := make(chan *Packet, 5), make(chan *Packet, 5)
+	return &chanPacketTransport{in: ch1, out: ch2}, &chanPacketTransport{in: ch2, out: ch1}
+}
+
+// WritePacket writes a packet to the remote.
+func (t *chanPacketTransport) WritePacket(p *Packet) error {
+	t.out <- p
+	return nil
+}
+
+// ReadPacket reads the next packet from the remote.
+func (t *chanPacketTransport) ReadPacket() (*Packet, error) {
+	pkt, ok := <-t.in
+	if !ok {
+		return nil, io.EOF
+	}
+	return pkt, nil
+}
+
+// Close closes the writer.
+func (t *chanPacketTransport) Close() error {
+	t.closeOnce.Do(func() {
+		close(t.out)
+	})
+	return nil
+}
+
+func TestPacketTransport(t *testing.T) {
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := NewServer(mux)
+
+	client := NewClient(NewPacketTransportOpenStream(func(ctx context.Context) (PacketTransport, error) {
+		clientTransport, serverTransport := newChanPacketTransportPair()
+		go func() {
+			_ = server.HandlePacketTransport(ctx, serverTransport)
+		}()
+		return clientTransport, nil
+	}))
+
+	in, out := RawMessage("hello"), new(RawMessage)
+	if err := client.Invoke(ctx, interopService, "Unary", &in, out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(*out) != "hello" {
+		t.Fatalf("expected hello got %q", string(*out))
+	}
+}
@@ -0,0 +1,105 @@
+package srpc
+
+import (
+	"sync"
+	"time"
+)
+
+// RateLimit configures a token bucket rate limit.
+type RateLimit struct {
+	// Rate is the number of calls per second added to the bucket.
+	Rate float64
+	// Burst is the max number of calls in the bucket.
+	Burst int
+}
+
+// RateLimitMux wraps a Mux to apply per-method call rate limits.
+//
+// Each method has a token bucket which starts full. Calls which exceed the
+// rate limit end with ErrRateLimited without invoking the handler. Methods
+// without a rate limit are not limited.
+type RateLimitMux struct {
+	// Mux is the underlying mux
+	Mux
+
+	// mtx guards below fields
+	mtx sync.Mutex
+	// buckets contains the token buckets by service and method
+	buckets map[rateLimitMuxKey]*tokenBucket
+}
+
+// rateLimitMuxKey is the key for a method rate limit.
+type rateLimitMuxKey struct {
+	serviceID, methodID string
+}
+
+// tokenBucket is a token bucket rate limiter.
+type tokenBucket struct {
+	// limit is the rate limit
+	limit RateLimit
+	// tokens is the number of tokens in the bucket
+	tokens float64
+	// last is the time tokens was last updated
+	last time.Time
+}
+
+// NewRateLimitMux constructs a new RateLimitMux wrapping a Mux.
+func NewRateLimitMux(mux Mux) *RateLimitMux {
+	return &RateLimitMux{Mux: mux, buckets: make(map[rateLimitMuxKey]*tokenBucket)}
+}
+
+// SetMethodRateLimit sets the rate limit for a method.
+// If limit.Burst <= 0, clears the rate limit.
+func (m *RateLimitMux) SetMethodRateLimit(serviceID, methodID string, limit RateLimit) {
+	key := rateLimitMuxKey{serviceID: serviceID, methodID: methodID}
+	m.mtx.Lock()
+	if limit.Burst > 0 {
+		m.buckets[key] = &tokenBucket{limit: limit, tokens: float64(limit.Burst), last: time.Now()}
+	} else {
+		delete(m.buckets, key)
+	}
+	m.mtx.Unlock()
+}
+
+// HasServiceMethod checks if the <service, method> pair has a handler.
+// Returns true if the underlying Mux is not a QueryableInvoker.
+func (m *RateLimitMux) HasServiceMethod(serviceID, methodID string) bool {
+	return invokerHasServiceMethod(m.Mux, serviceID, methodID)
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+// Returns false, nil if not found.
+// If service string is empty, ignore it.
+func (m *RateLimitMux) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	m.mtx.Lock()
+	bucket := m.buckets[rateLimitMuxKey{serviceID: serviceID, methodID: methodID}]
+	allowed := bucket == nil || bucket.take(time.Now())
+	m.mtx.Unlock()
+	if !allowed {
+		return true, ErrRateLimited
+	}
+	return m.Mux.InvokeMethod(serviceID, methodID, strm)
+}
+
+// take refills the bucket and takes a token.
+// Returns false if the bucket was empty.
+func (b *tokenBucket) take(now time.Time) bool {
+	if elapsed := now.Sub(b.last).Seconds(); elapsed > 0 {
+		b.tokens += elapsed * b.limit.Rate
+		if burst := float64(b.limit.Burst); b.tokens > burst {
+			b.tokens = burst
+		}
+	}
+	b.last = now
+	if b.tokens < 1 {
+		return false
+	}
+	b.tokens--
+	return true
+}
+
+// _ is a type assertion
+var (
+	_ Mux              = ((*RateLimitMux)(nil))
+	_ QueryableInvoker = ((*RateLimitMux)(nil))
+)
@@ -0,0 +1,52 @@
+package srpc
+
+import (
+	"errors"
+	"testing"
+	"time"
+)
+
+func TestRateLimitMux(t *testing.T) {
+	handlerErr := errors.New("handled")
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(handlerErr)); err != nil {
+		t.Fatal(err.Error())
+	}
+	rmux := NewRateLimitMux(mux)
+	rmux.SetMethodRateLimit("test.Service", "Method", RateLimit{Rate: 0, Burst: 2})
+
+	// the bucket starts full.
+	for i := 0; i < 2; i++ {
+		if ok, err := rmux.InvokeMethod("test.Service", "Method", nil); !ok || err != handlerErr {
+			t.Fatalf("call %d: expected %v got %v, %v", i, handlerErr, ok, err)
+		}
+	}
+	if ok, err := rmux.InvokeMethod("test.Service", "Method", nil); !ok || err != ErrRateLimited {
+		t.Fatalf("expected %v got %v, %v", ErrRateLimited, ok, err)
+	}
+
+	// clearing the limit allows calls again.
+	rmux.SetMethodRateLimit("test.Service", "Method", RateLimit{})
+	if ok, err := rmux.InvokeMethod("test.Service", "Method", nil); !ok || err != handlerErr {
+		t.Fatalf("expected %v got %v, %v", handlerErr, ok, err)
+	}
+}
+
+func TestTokenBucket(t *testing.T) {
+	now := time.Now()
+	b := &tokenBucket{limit: RateLimit{Rate: 10, Burst: 1}, tokens: 1, last: now}
+	if !b.take(now) {
+		t.Fatal("expected the first call to be allowed")
+	}
+	if b.take(now) {
+		t.Fatal("expected the second call to be limited")
+	}
+	// one token is added every 100ms.
+	if !b.take(now.Add(100 * time.Millisecond)) {
+		t.Fatal("expected the bucket to refill")
+	}
+	// the bucket does not refill past the burst.
+	if !b.take(now.Add(time.Hour)) || b.take(now.Add(time.Hour)) {
+		t.Fatal("expected the bucket to hold at most one token")
+	}
+}
@@ -0,0 +1,25 @@
+package srpc
+
+// RawMessage is a Message containing the raw encoded message data.
+//
+// Useful to forward or echo messages without decoding them.
+type RawMessage []byte
+
+// MarshalVT returns the raw message data.
+func (m *RawMessage) MarshalVT() ([]byte, error) {
+	return *m, nil
+}
+
+// SizeVT returns the size of the raw message data.
+func (m *RawMessage) SizeVT() int {
+	return len(*m)
+}
+
+// UnmarshalVT copies the data into the message.
+func (m *RawMessage) UnmarshalVT(data []byte) error {
+	*m = append((*m)[:0], data...)
+	return nil
+}
+
+// _ is a type assertion
+var _ Message = ((*RawMessage)(nil))
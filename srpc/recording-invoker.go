@@ -0,0 +1,88 @@
+package srpc
+
+import (
+	"io"
+	"sync"
+)
+
+// RecordedCall is a call recorded by RecordingInvoker.
+type RecordedCall struct {
+	// Service is the service id.
+	Service string
+	// Method is the method id.
+	Method string
+	// FirstMsg is the first message sent by the client.
+	// Nil if the client closed the stream without sending a message.
+	FirstMsg []byte
+}
+
+// recordedResponse is a canned response for a method.
+type recordedResponse struct {
+	// data is the response message
+	data []byte
+	// err is the error to return
+	err error
+}
+
+// RecordingInvoker is an Invoker which records every call.
+//
+// Intended for testing clients without a real handler: each call receives the
+// first message from the client and returns the response set with SetResponse.
+// Calls to methods without a response return unimplemented. Concurrency safe.
+type RecordingInvoker struct {
+	// mtx guards below fields
+	mtx sync.Mutex
+	// calls is the list of recorded calls
+	calls []RecordedCall
+	// responses contains the canned responses keyed by service/method
+	responses map[string]recordedResponse
+}
+
+// NewRecordingInvoker constructs a new RecordingInvoker.
+func NewRecordingInvoker() *RecordingInvoker {
+	return &RecordingInvoker{responses: make(map[string]recordedResponse)}
+}
+
+// SetResponse sets the canned response for a method.
+//
+// If err is set, the call returns err instead of sending data.
+func (i *RecordingInvoker) SetResponse(serviceID, methodID string, data []byte, err error) {
+	i.mtx.Lock()
+	i.responses[serviceID+"/"+methodID] = recordedResponse{data: data, err: err}
+	i.mtx.Unlock()
+}
+
+// Calls returns a copy of the list of recorded calls.
+func (i *RecordingInvoker) Calls() []RecordedCall {
+	i.mtx.Lock()
+	defer i.mtx.Unlock()
+	return append([]RecordedCall(nil), i.calls...)
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+func (i *RecordingInvoker) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	call := RecordedCall{Service: serviceID, Method: methodID}
+	var msg RawMessage
+	if err := strm.MsgRecv(&msg); err == nil {
+		call.FirstMsg = append([]byte{}, msg...)
+	} else if err != io.EOF {
+		return true, err
+	}
+
+	i.mtx.Lock()
+	i.calls = append(i.calls, call)
+	resp, ok := i.responses[serviceID+"/"+methodID]
+	i.mtx.Unlock()
+
+	if !ok {
+		return false, nil
+	}
+	if resp.err != nil {
+		return true, resp.err
+	}
+	out := RawMessage(resp.data)
+	return true, strm.MsgSend(&out)
+}
+
+// _ is a type assertion
+var _ Invoker = ((*RecordingInvoker)(nil))
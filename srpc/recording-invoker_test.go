@@ -0,0 +1,41 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+func TestRecordingInvoker(t *testing.T) {
+	ctx := context.Background()
+	invoker := NewRecordingInvoker()
+	invoker.SetResponse("test.Service", "Method", []byte("response"), nil)
+	respErr := errors.New("failed")
+	invoker.SetResponse("test.Service", "Fail", nil, respErr)
+	client := NewDirectClient(invoker)
+
+	in, out := RawMessage("request"), RawMessage(nil)
+	if err := client.Invoke(ctx, "test.Service", "Method", &in, &out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != "response" {
+		t.Fatalf("unexpected response: %q", out)
+	}
+	if err := client.Invoke(ctx, "test.Service", "Fail", &in, &out); err != respErr {
+		t.Fatalf("expected %v got %v", respErr, err)
+	}
+	if err := client.Invoke(ctx, "test.Service", "Other", &in, &out); err != ErrUnimplemented {
+		t.Fatalf("expected unimplemented got %v", err)
+	}
+
+	calls := invoker.Calls()
+	if len(calls) != 3 {
+		t.Fatalf("expected 3 calls got %d", len(calls))
+	}
+	for i, method := range []string{"Method", "Fail", "Other"} {
+		call := calls[i]
+		if call.Service != "test.Service" || call.Method != method || string(call.FirstMsg) != "request" {
+			t.Fatalf("unexpected call: %v", call)
+		}
+	}
+}
@@ -0,0 +1,80 @@
+package srpc
+
+import (
+	"context"
+	"time"
+)
+
+// recvTimeoutStream wraps a Stream to apply a timeout to every MsgRecv.
+type recvTimeoutStream struct {
+	// Stream is the underlying stream
+	Stream
+	// timeout is the max time to wait for each message
+	timeout time.Duration
+}
+
+// NewRecvTimeoutStream wraps a Stream to apply a timeout to every MsgRecv.
+//
+// The timeout restarts for each message. If no message is received within the
+// timeout, returns ErrStreamIdle and leaves the stream open. Other calls are
+// passed through. If timeout <= 0, returns strm.
+//
+// If strm does not support MsgRecvContext, as the streams in this package do,
+// a pending MsgRecv can only be interrupted by closing the stream: on timeout
+// the stream is closed and ErrStreamIdle is returned.
+func NewRecvTimeoutStream(strm Stream, timeout time.Duration) Stream {
+	if timeout <= 0 {
+		return strm
+	}
+	return &recvTimeoutStream{Stream: strm, timeout: timeout}
+}
+
+// MsgRecv receives an incoming message from the remote.
+// Parses the message into the object at msg.
+func (s *recvTimeoutStream) MsgRecv(msg Message) error {
+	return s.MsgRecvContext(s.Stream.Context(), msg)
+}
+
+// MsgRecvContext receives an incoming message from the remote.
+// ctx must be derived from the stream Context.
+func (s *recvTimeoutStream) MsgRecvContext(ctx context.Context, msg Message) error {
+	c, ok := streamAs[msgRecvContexter](s.Stream)
+	if !ok {
+		return s.msgRecvOrClose(msg)
+	}
+	recvCtx, recvCtxCancel := context.WithTimeout(ctx, s.timeout)
+	defer recvCtxCancel()
+	err := c.MsgRecvContext(recvCtx, msg)
+	if err == context.Canceled && recvCtx.Err() == context.DeadlineExceeded && ctx.Err() == nil {
+		return ErrStreamIdle
+	}
+	return err
+}
+
+// msgRecvOrClose calls MsgRecv and closes the stream if the timeout elapses.
+// Used if the stream does not support MsgRecvContext.
+func (s *recvTimeoutStream) msgRecvOrClose(msg Message) error {
+	errCh := make(chan error, 1)
+	go func() {
+		errCh <- s.Stream.MsgRecv(msg)
+	}()
+	timer := time.NewTimer(s.timeout)
+	defer timer.Stop()
+	select {
+	case err := <-errCh:
+		return err
+	case <-timer.C:
+	}
+	// close the stream and wait for MsgRecv to return before msg is reused.
+	_ = s.Stream.Close()
+	<-errCh
+	return ErrStreamIdle
+}
+
+// Unwrap returns the underlying Stream.
+func (s *recvTimeoutStream) Unwrap() Stream {
+	return s.Stream
+}
+
+// _ is a type assertion
+var _ Stream = ((*recvTimeoutStream)(nil))
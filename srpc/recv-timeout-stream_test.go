@@ -0,0 +1,69 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+	"time"
+)
+
+func TestRecvTimeoutStream(t *testing.T) {
+	ctx := context.Background()
+	s1, s2 := NewPipeStream(ctx)
+	strm := NewRecvTimeoutStream(s2, 50*time.Millisecond)
+
+	msg := RawMessage("hello")
+	if err := s1.MsgSend(&msg); err != nil {
+		t.Fatal(err.Error())
+	}
+	var out RawMessage
+	if err := strm.MsgRecv(&out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != "hello" {
+		t.Fatalf("unexpected message: %q", out)
+	}
+	if err := strm.MsgRecv(&out); err != ErrStreamIdle {
+		t.Fatalf("expected %v got %v", ErrStreamIdle, err)
+	}
+	if strm.Context().Err() != nil {
+		t.Fatal("expected the stream to remain open")
+	}
+
+	// the stream can still be used after the timeout.
+	msg = RawMessage("world")
+	if err := s1.MsgSend(&msg); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := strm.MsgRecv(&out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != "world" {
+		t.Fatalf("unexpected message: %q", out)
+	}
+}
+
+func TestRecvTimeoutStream_DirectClient(t *testing.T) {
+	// the handler never sends a message.
+	mux := NewMux()
+	handler := newTestHandler(func(strm Stream) error {
+		<-strm.Context().Done()
+		return nil
+	})
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	clientStrm, err := NewDirectClient(mux).NewStream(context.Background(), "test.Service", "Method", nil)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer clientStrm.Close()
+
+	strm := NewRecvTimeoutStream(clientStrm, 50*time.Millisecond)
+	var out RawMessage
+	if err := strm.MsgRecv(&out); err != ErrStreamIdle {
+		t.Fatalf("expected %v got %v", ErrStreamIdle, err)
+	}
+	if strm.Context().Err() != nil {
+		t.Fatal("expected the stream to remain open")
+	}
+}
@@ -0,0 +1,27 @@
+package srpc
+
+import "io"
+
+// ServeStream sends the messages returned by next to the remote.
+//
+// Calls next until it returns an error. Returns nil if next returns io.EOF,
+// otherwise returns the error. Intended for server streaming handlers:
+//
+//	return srpc.ServeStream(strm, func() (*MyMsg, error) { ... })
+//
+// Returning the error from the handler ends the rpc with the error, returning
+// nil completes the rpc.
+func ServeStream[T Message](strm Stream, next func() (T, error)) error {
+	for {
+		msg, err := next()
+		if err != nil {
+			if err == io.EOF {
+				return nil
+			}
+			return err
+		}
+		if err := strm.MsgSend(msg); err != nil {
+			return err
+		}
+	}
+}
@@ -2,11 +2,27 @@ package srpc
 
 import (
 	"context"
+	"fmt"
 	"io"
+	"sync"
 
 	"github.com/pkg/errors"
 )
 
+// DataAfterCompletePolicy controls how the server handles data received from
+// the client after the handler returned and the rpc was completed.
+type DataAfterCompletePolicy int
+
+const (
+	// DataAfterCompleteIgnore drops the data.
+	// This is the default.
+	DataAfterCompleteIgnore DataAfterCompletePolicy = iota
+	// DataAfterCompleteReject aborts the stream with ErrCompleted.
+	//
+	// The error is included in the ShutdownReport.
+	DataAfterCompleteReject
+)
+
 // ServerRPC represents the server side of an on-going RPC call message stream.
 // Not concurrency safe: use a mutex if calling concurrently.
 type ServerRPC struct {
@@ -16,8 +32,10 @@ type ServerRPC struct {
 	ctxCancel context.CancelFunc
 	// writer is the writer to write messages to
 	writer Writer
-	// mux is the mux to handle calls
-	mux Mux
+	// invoker handles the calls
+	invoker Invoker
+	// ext contains the extensions for the rpc
+	ext *Extensions
 	// service is the rpc service
 	service string
 	// method is the rpc method
@@ -28,20 +46,56 @@ type ServerRPC struct {
 	// dataChClosed is a flag set after dataCh is closed.
 	// controlled by HandlePacket.
 	dataChClosed bool
+	// recvClosed is closed when dataCh is closed.
+	recvClosed chan struct{}
 	// clientErr is an error set by the client.
 	// before dataCh is closed, managed by HandlePacket.
 	// immutable after dataCh is closed or ctxCancel
 	clientErr error
+	// callStartData indicates the first message was included in CallStart.
+	// set by HandleCallStart.
+	callStartData bool
+	// dataAfterCompletePolicy handles data received after the handler returned.
+	dataAfterCompletePolicy DataAfterCompletePolicy
+	// recvLimitMsgs is the max number of messages to receive, if > 0
+	recvLimitMsgs int
+	// recvLimitBytes is the max total message size to receive, if > 0
+	recvLimitBytes int64
+	// recvMsgs is the number of messages received.
+	recvMsgs int
+	// recvBytes is the total size of the messages received.
+	recvBytes int64
+
+	// handlerDoneCh is closed after the handler returned and the rpc was completed.
+	handlerDoneCh chan struct{}
+
+	// teardownMtx guards below fields
+	teardownMtx sync.Mutex
+	// handlerStarted indicates the handler was started.
+	handlerStarted bool
+	// tornDown indicates WaitTeardown was called: no handler will be started.
+	tornDown bool
+	// handlerDone indicates the handler returned and the rpc was completed.
+	handlerDone bool
+	// handlerErr is the error returned by the rpc handler.
+	handlerErr error
+	// teardownErrs contains errors writing the final packet & closing.
+	teardownErrs []error
 }
 
 // NewServerRPC constructs a new ServerRPC session.
 // note: call SetWriter before handling any incoming messages.
-func NewServerRPC(ctx context.Context, mux Mux) *ServerRPC {
+//
+// invoker is usually a Mux.
+func NewServerRPC(ctx context.Context, invoker Invoker) *ServerRPC {
 	rpc := &ServerRPC{
-		dataCh: make(chan []byte, 5),
-		mux:    mux,
+		dataCh:        newRecvBuffer(0),
+		recvClosed:    make(chan struct{}),
+		invoker:       invoker,
+		ext:           NewExtensions(),
+		handlerDoneCh: make(chan struct{}),
 	}
-	rpc.ctx, rpc.ctxCancel = context.WithCancel(ctx)
+	rpc.ctx, rpc.ctxCancel = context.WithCancel(WithExtensions(ctx, rpc.ext))
 	return rpc
 }
 
@@ -50,11 +104,63 @@ func (r *ServerRPC) SetWriter(w Writer) {
 	r.writer = w
 }
 
+// SetRecvBufferSize sets the number of incoming messages to buffer.
+//
+// The read pump blocks when the buffer is full until the handler calls
+// MsgRecv. If size <= 0 uses DefaultRecvBufferSize.
+// Must be called before handling any incoming packets.
+func (r *ServerRPC) SetRecvBufferSize(size int) {
+	r.dataCh = newRecvBuffer(size)
+}
+
+// SetRecvLimit sets the max number of messages and total message bytes to
+// receive from the client. Zero or less is unlimited.
+//
+// If exceeded, stops reading from the client and MsgRecv returns
+// ErrRecvLimitExceeded after the buffered messages.
+// Must be called before handling any incoming packets.
+func (r *ServerRPC) SetRecvLimit(maxMsgs int, maxBytes int64) {
+	r.recvLimitMsgs, r.recvLimitBytes = maxMsgs, maxBytes
+}
+
+// SetDataAfterCompletePolicy sets the policy for data received after the
+// handler returned. Defaults to DataAfterCompleteIgnore.
+func (r *ServerRPC) SetDataAfterCompletePolicy(policy DataAfterCompletePolicy) {
+	r.dataAfterCompletePolicy = policy
+}
+
 // Context is canceled when the ServerRPC is no longer valid.
 func (r *ServerRPC) Context() context.Context {
 	return r.ctx
 }
 
+// Extensions returns the extensions for the rpc.
+//
+// Attached to the rpc context: handlers can use GetExtensions.
+func (r *ServerRPC) Extensions() *Extensions {
+	return r.ext
+}
+
+// Service returns the rpc service ID.
+// Set once CallStart is handled: call after Wait returns.
+func (r *ServerRPC) Service() string {
+	return r.service
+}
+
+// Method returns the rpc method ID.
+// Set once CallStart is handled: call after Wait returns.
+func (r *ServerRPC) Method() string {
+	return r.method
+}
+
+// RequestViaCallStart returns if the first message was included in CallStart.
+//
+// Returns false if the client sent the first message as a separate CallData.
+// Intended for diagnostics: the result is set once CallStart is handled.
+func (r *ServerRPC) RequestViaCallStart() bool {
+	return r.callStartData
+}
+
 // Wait waits for the RPC to finish.
 func (r *ServerRPC) Wait(ctx context.Context) error {
 	select {
@@ -65,21 +171,51 @@ func (r *ServerRPC) Wait(ctx context.Context) error {
 	return r.clientErr
 }
 
+// ReadOne reads a single message and returns.
+//
+// returns io.EOF if the stream ended.
+func (r *ServerRPC) ReadOne() ([]byte, error) {
+	return r.ReadOneContext(r.ctx)
+}
+
+// ReadOneContext reads a single message and returns.
+//
+// returns context.Canceled if ctx or the rpc is canceled.
+// returns io.EOF if the stream ended.
+func (r *ServerRPC) ReadOneContext(ctx context.Context) ([]byte, error) {
+	select {
+	case <-ctx.Done():
+		return nil, context.Canceled
+	case <-r.ctx.Done():
+		return nil, context.Canceled
+	case data, ok := <-r.dataCh:
+		if !ok {
+			if err := r.clientErr; err != nil {
+				return nil, err
+			}
+			return nil, io.EOF
+		}
+		return data, nil
+	}
+}
+
 // HandleStreamClose handles the incoming stream closing w/ optional error.
 func (r *ServerRPC) HandleStreamClose(closeErr error) {
 	if r.dataChClosed {
 		return
 	}
 	if closeErr != nil {
+		if closeErr != io.EOF && closeErr != context.Canceled {
+			closeErr = &TransportError{Err: closeErr}
+		}
 		if r.clientErr == nil {
 			r.clientErr = closeErr
 		}
-		if closeErr != io.EOF && closeErr != context.Canceled {
+		if IsTransportError(closeErr) {
 			r.Close()
 		}
 	}
-	r.dataChClosed = true
-	close(r.dataCh)
+	r.closeDataCh()
 }
 
 // HandlePacket handles an incoming parsed message packet.
@@ -118,15 +254,29 @@ func (r *ServerRPC) HandleCallStart(pkt *CallStart) error {
 		if data == nil {
 			data = []byte{}
 		}
-		select {
-		case r.dataCh <- data:
-		default:
-			// the channel should be empty w/ a buffer capacity of 5 here.
-			return errors.New("data channel was full, expected empty")
+		if r.checkRecvLimit(data) {
+			// the handler reports the error to the client.
+			r.clientErr = ErrRecvLimitExceeded
+			r.closeDataCh()
+		} else {
+			select {
+			case r.dataCh <- data:
+			default:
+				// the channel should be empty w/ a buffer capacity of at least 1 here.
+				return errors.New("data channel was full, expected empty")
+			}
 		}
+		r.callStartData = true
 	}
 
 	// invoke the rpc
+	r.teardownMtx.Lock()
+	if r.tornDown {
+		r.teardownMtx.Unlock()
+		return context.Canceled
+	}
+	r.handlerStarted = true
+	r.teardownMtx.Unlock()
 	go r.invokeRPC()
 
 	return nil
@@ -138,7 +288,24 @@ func (r *ServerRPC) HandleCallData(pkt *CallData) error {
 		return ErrCompleted
 	}
 
-	if data := pkt.GetData(); len(data) != 0 || pkt.GetDataIsZero() {
+	data := pkt.GetData()
+	hasData := len(data) != 0 || pkt.GetDataIsZero()
+	if hasData && r.isHandlerDone() {
+		if r.dataAfterCompletePolicy == DataAfterCompleteReject {
+			r.teardownMtx.Lock()
+			r.teardownErrs = append(r.teardownErrs, ErrCompleted)
+			r.teardownMtx.Unlock()
+			return ErrCompleted
+		}
+		// drop the data: nothing will read it.
+		hasData = false
+	}
+	if hasData && r.checkRecvLimit(data) {
+		r.clientErr = ErrRecvLimitExceeded
+		r.closeDataCh()
+		return ErrRecvLimitExceeded
+	}
+	if hasData {
 		select {
 		case <-r.ctx.Done():
 			return context.Canceled
@@ -149,12 +316,11 @@ func (r *ServerRPC) HandleCallData(pkt *CallData) error {
 	complete := pkt.GetComplete()
 	if err := pkt.GetError(); len(err) != 0 {
 		complete = true
-		r.clientErr = errors.New(err)
+		r.clientErr = ErrorFromString(err)
 	}
 
 	if complete {
-		r.dataChClosed = true
-		close(r.dataCh)
+		r.closeDataCh()
 	}
 
 	return nil
@@ -164,15 +330,113 @@ func (r *ServerRPC) HandleCallData(pkt *CallData) error {
 func (r *ServerRPC) invokeRPC() {
 	// ctx := r.ctx
 	serviceID, methodID := r.service, r.method
-	strm := NewMsgStream(r.ctx, r.writer, r.dataCh)
-	ok, err := r.mux.InvokeMethod(serviceID, methodID, strm)
-	if err == nil && !ok {
+	ctx, endTrace := startTraceTask(r.ctx, TraceTaskServerCall, serviceID, methodID)
+	var err error
+	var completed bool
+	if q, isQueryable := r.invoker.(QueryableInvoker); isQueryable && !q.HasServiceMethod(serviceID, methodID) {
+		// skip invoking to avoid consuming the stream
 		err = ErrUnimplemented
+	} else {
+		strm := NewMsgStream(ctx, r.writer, r)
+		var ok bool
+		ok, err = r.invoker.InvokeMethod(serviceID, methodID, strm)
+		if err == nil && !ok {
+			err = ErrUnimplemented
+		}
+		completed = strm.IsSendClosed()
+	}
+	endTrace(err)
+	var writeErr error
+	if !completed || err != nil {
+		// skip the final packet if the handler already completed the rpc.
+		outPkt := NewCallDataPacket(nil, false, true, err)
+		writeErr = r.writer.WritePacket(outPkt)
 	}
-	outPkt := NewCallDataPacket(nil, false, true, err)
-	_ = r.writer.WritePacket(outPkt)
-	_ = r.writer.Close()
+	closeErr := r.writer.Close()
+	r.teardownMtx.Lock()
+	r.handlerDone = true
+	r.handlerErr = err
+	r.teardownErrs = append(r.teardownErrs, writeErr, closeErr)
+	r.teardownMtx.Unlock()
+	r.ctxCancel()
+	close(r.handlerDoneCh)
+}
+
+// WaitTeardown cancels the rpc and waits for the teardown to complete.
+//
+// If the handler has not completed the rpc, closes the writer to unblock any
+// pending writes and prevents the handler from starting. Then waits for the
+// handler, if started, to return. The handler must return when its context is
+// canceled. Call after Wait returns.
+func (r *ServerRPC) WaitTeardown() {
+	r.teardownMtx.Lock()
+	started, done := r.handlerStarted, r.handlerDone
+	r.tornDown = true
+	r.teardownMtx.Unlock()
 	r.ctxCancel()
+	if !done {
+		_ = r.writer.Close()
+	}
+	if started {
+		<-r.handlerDoneCh
+	}
+}
+
+// ShutdownReport returns a report of the errors that occurred while the rpc
+// shut down: the handler error, the client or read error, and any errors
+// writing the final packet or closing the writer.
+//
+// Call after Wait returns.
+func (r *ServerRPC) ShutdownReport() *ShutdownReport {
+	r.teardownMtx.Lock()
+	errs := append([]error{r.handlerErr, r.clientErr}, r.teardownErrs...)
+	r.teardownMtx.Unlock()
+	return newShutdownReport(errs...)
+}
+
+// checkRecvLimit counts a received message.
+// Returns true if the recv limit was exceeded.
+func (r *ServerRPC) checkRecvLimit(data []byte) bool {
+	r.recvMsgs++
+	r.recvBytes += int64(len(data))
+	return (r.recvLimitMsgs > 0 && r.recvMsgs > r.recvLimitMsgs) ||
+		(r.recvLimitBytes > 0 && r.recvBytes > r.recvLimitBytes)
+}
+
+// isHandlerDone checks if the handler returned and the rpc was completed.
+func (r *ServerRPC) isHandlerDone() bool {
+	r.teardownMtx.Lock()
+	defer r.teardownMtx.Unlock()
+	return r.handlerDone
+}
+
+// PendingRecv returns the number of buffered incoming messages not yet read.
+// Safe to call concurrently.
+func (r *ServerRPC) PendingRecv() int {
+	return len(r.dataCh)
+}
+
+// RecvClosed returns a channel closed when the remote closed its send side.
+func (r *ServerRPC) RecvClosed() <-chan struct{} {
+	return r.recvClosed
+}
+
+// String returns a description of the rpc for debugging.
+//
+// The service and method are set once CallStart is handled: call from the
+// handler or after Wait returns.
+func (r *ServerRPC) String() string {
+	return fmt.Sprintf(
+		"ServerRPC(%s/%s recv_closed=%v handler_done=%v)",
+		r.service, r.method, isChanClosed(r.recvClosed), r.isHandlerDone(),
+	)
+}
+
+// closeDataCh closes the data channel and signals recvClosed.
+func (r *ServerRPC) closeDataCh() {
+	r.dataChClosed = true
+	close(r.dataCh)
+	close(r.recvClosed)
 }
 
 // Close releases any resources held by the ServerRPC.
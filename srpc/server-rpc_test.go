@@ -0,0 +1,186 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"testing"
+)
+
+// discardWriter is a Writer which discards all packets.
+type discardWriter struct{}
+
+// WritePacket writes a packet to the remote.
+func (discardWriter) WritePacket(p *Packet) error { return nil }
+
+// Close closes the writer.
+func (discardWriter) Close() error { return nil }
+
+func TestServerRPC_RequestViaCallStart(t *testing.T) {
+	ctx := context.Background()
+
+	withData := NewServerRPC(ctx, NewMux())
+	withData.SetWriter(discardWriter{})
+	if err := withData.HandlePacket(NewCallStartPacket("test.Service", "Method", []byte("hello"), false)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if !withData.RequestViaCallStart() {
+		t.Fatal("expected request via call start")
+	}
+
+	withEmpty := NewServerRPC(ctx, NewMux())
+	withEmpty.SetWriter(discardWriter{})
+	if err := withEmpty.HandlePacket(NewCallStartPacket("test.Service", "Method", nil, true)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if !withEmpty.RequestViaCallStart() {
+		t.Fatal("expected empty request via call start")
+	}
+
+	separate := NewServerRPC(ctx, NewMux())
+	separate.SetWriter(discardWriter{})
+	if err := separate.HandlePacket(NewCallStartPacket("test.Service", "Method", nil, false)); err != nil {
+		t.Fatal(err.Error())
+	}
+	if separate.RequestViaCallStart() {
+		t.Fatal("expected request via separate call data")
+	}
+}
+
+// testHandler is a Handler with a single method "Method".
+type testHandler struct {
+	// serviceID is the service ID, if empty uses "test.Service"
+	serviceID string
+	// invoke handles all calls
+	invoke func(strm Stream) error
+}
+
+// newTestHandler constructs a testHandler for "test.Service".
+func newTestHandler(invoke func(strm Stream) error) *testHandler {
+	return &testHandler{invoke: invoke}
+}
+
+// newErrHandler constructs a testHandler which returns err for all calls.
+func newErrHandler(err error) *testHandler {
+	return newTestHandler(func(strm Stream) error { return err })
+}
+
+// GetServiceID returns the ID of the service.
+func (h *testHandler) GetServiceID() string {
+	if h.serviceID != "" {
+		return h.serviceID
+	}
+	return "test.Service"
+}
+
+// GetMethodIDs returns the list of methods for the service.
+func (h *testHandler) GetMethodIDs() []string { return []string{"Method"} }
+
+// InvokeMethod invokes the method matching the service & method ID.
+func (h *testHandler) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	return true, h.invoke(strm)
+}
+
+// closeErrWriter is a Writer which returns an error on Close.
+type closeErrWriter struct {
+	discardWriter
+	err error
+}
+
+// Close closes the writer.
+func (w *closeErrWriter) Close() error { return w.err }
+
+func TestServerRPC_ShutdownReport(t *testing.T) {
+	ctx := context.Background()
+	handlerErr, closeErr := errors.New("handler failed"), errors.New("close failed")
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(handlerErr)); err != nil {
+		t.Fatal(err.Error())
+	}
+	rpc := NewServerRPC(ctx, mux)
+	rpc.SetWriter(&closeErrWriter{err: closeErr})
+	if err := rpc.HandlePacket(NewCallStartPacket("test.Service", "Method", nil, true)); err != nil {
+		t.Fatal(err.Error())
+	}
+	_ = rpc.Wait(ctx)
+
+	report := rpc.ShutdownReport()
+	if report.Err != handlerErr {
+		t.Fatalf("expected primary error %v got %v", handlerErr, report.Err)
+	}
+	if len(report.Secondary) != 1 || report.Secondary[0] != closeErr {
+		t.Fatalf("expected secondary error %v got %v", closeErr, report.Secondary)
+	}
+}
+
+func TestServerRPC_DataAfterComplete(t *testing.T) {
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(newErrHandler(nil)); err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, policy := range []DataAfterCompletePolicy{DataAfterCompleteIgnore, DataAfterCompleteReject} {
+		rpc := NewServerRPC(ctx, mux)
+		rpc.SetWriter(discardWriter{})
+		rpc.SetDataAfterCompletePolicy(policy)
+		if err := rpc.HandlePacket(NewCallStartPacket("test.Service", "Method", nil, true)); err != nil {
+			t.Fatal(err.Error())
+		}
+		_ = rpc.Wait(ctx)
+
+		err := rpc.HandlePacket(NewCallDataPacket([]byte("late"), false, false, nil))
+		switch policy {
+		case DataAfterCompleteIgnore:
+			if err != nil {
+				t.Fatalf("expected late data to be ignored got %v", err)
+			}
+		case DataAfterCompleteReject:
+			if err != ErrCompleted {
+				t.Fatalf("expected %v got %v", ErrCompleted, err)
+			}
+			if report := rpc.ShutdownReport(); report.Err != ErrCompleted {
+				t.Fatalf("expected report error %v got %v", ErrCompleted, report.Err)
+			}
+		}
+	}
+}
+
+func TestServerRPC_RecvBufferSize(t *testing.T) {
+	rpc := NewServerRPC(context.Background(), NewMux())
+	if size := cap(rpc.dataCh); size != DefaultRecvBufferSize {
+		t.Fatalf("expected default buffer size %d got %d", DefaultRecvBufferSize, size)
+	}
+	rpc.SetRecvBufferSize(64)
+	if size := cap(rpc.dataCh); size != 64 {
+		t.Fatalf("expected buffer size 64 got %d", size)
+	}
+}
+
+func TestServerRPC_RecvLimit(t *testing.T) {
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	writer := &recordWriter{}
+	rpc := NewServerRPC(ctx, mux)
+	rpc.SetWriter(writer)
+	rpc.SetRecvLimit(2, 0)
+
+	in := []*Packet{
+		NewCallStartPacket(interopService, "ClientStream", nil, false),
+		interopCallData("a"),
+		interopCallData("b"),
+	}
+	for _, pkt := range in {
+		if err := rpc.HandlePacket(pkt); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if err := rpc.HandlePacket(interopCallData("c")); err != ErrRecvLimitExceeded {
+		t.Fatalf("expected %v got %v", ErrRecvLimitExceeded, err)
+	}
+	if err := rpc.Wait(ctx); err != ErrRecvLimitExceeded {
+		t.Fatalf("expected %v got %v", ErrRecvLimitExceeded, err)
+	}
+	writer.assertPackets(t, []*Packet{NewCallDataPacket(nil, false, true, ErrRecvLimitExceeded)})
+}
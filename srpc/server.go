@@ -3,6 +3,8 @@ package srpc
 import (
 	"context"
 	"io"
+	"net"
+	"time"
 
 	"github.com/libp2p/go-libp2p-core/network"
 )
@@ -11,13 +13,131 @@ import (
 type Server struct {
 	// mux is the srpc mux
 	mux Mux
+	// invoker handles the rpcs in place of mux, if set
+	invoker Invoker
+	// observer is the observer, if any
+	observer Observer
+	// decodeErrPolicy is the policy for malformed incoming packets
+	decodeErrPolicy DecodeErrorPolicy
+	// onDecodeErr is called with skipped packet errors, if set
+	onDecodeErr func(err error)
+	// dataAfterCompletePolicy is the policy for data after the rpc completed
+	dataAfterCompletePolicy DataAfterCompletePolicy
+	// flushPolicy is the policy for flushing written packets
+	flushPolicy FlushPolicy
+	// flushDelay is the max delay for FlushBuffered
+	flushDelay time.Duration
+	// flushBatchSize is the max size of buffered packets for FlushBuffered
+	flushBatchSize int
+	// recvBufferSize is the number of incoming messages to buffer per rpc
+	recvBufferSize int
+	// recvLimitMsgs is the max number of messages to receive per rpc
+	recvLimitMsgs int
+	// recvLimitBytes is the max total message size to receive per rpc
+	recvLimitBytes int64
+	// ctxFactory derives the rpc context from the stream context, if set
+	ctxFactory func(ctx context.Context) context.Context
+}
+
+// ServerOption configures a Server.
+type ServerOption func(s *Server)
+
+// WithObserver sets an Observer to call for the packets and rpcs of the Server.
+func WithObserver(obs Observer) ServerOption {
+	return func(s *Server) {
+		s.observer = obs
+	}
+}
+
+// WithInvoker sets an Invoker to handle the rpcs in place of the Mux.
+//
+// Use with InvokerRouter to select the Invoker for each call. GetMux still
+// returns the Mux passed to NewServer.
+func WithInvoker(invoker Invoker) ServerOption {
+	return func(s *Server) {
+		s.invoker = invoker
+	}
+}
+
+// WithDecodeErrorPolicy sets the policy for malformed incoming packets.
+//
+// onErr is optional, called with the error for each skipped packet.
+func WithDecodeErrorPolicy(policy DecodeErrorPolicy, onErr func(err error)) ServerOption {
+	return func(s *Server) {
+		s.decodeErrPolicy, s.onDecodeErr = policy, onErr
+	}
+}
+
+// WithDataAfterCompletePolicy sets the policy for data received from the
+// client after the handler returned.
+func WithDataAfterCompletePolicy(policy DataAfterCompletePolicy) ServerOption {
+	return func(s *Server) {
+		s.dataAfterCompletePolicy = policy
+	}
+}
+
+// WithFlushPolicy sets the policy for flushing packets written to the client.
+//
+// flushDelay is the max delay for FlushBuffered from the first buffered packet
+// until the flush, if <= 0 uses DefaultFlushDelay.
+func WithFlushPolicy(policy FlushPolicy, flushDelay time.Duration) ServerOption {
+	return func(s *Server) {
+		s.flushPolicy, s.flushDelay = policy, flushDelay
+	}
+}
+
+// WithFlushBatchSize sets the max size in bytes of packets buffered by
+// FlushBuffered before flushing, if <= 0 uses DefaultFlushBatchSize.
+func WithFlushBatchSize(size int) ServerOption {
+	return func(s *Server) {
+		s.flushBatchSize = size
+	}
+}
+
+// WithRecvBufferSize sets the number of incoming messages to buffer per rpc.
+//
+// The read pump blocks when the buffer is full until the handler calls
+// MsgRecv, which applies backpressure to the client. Larger buffers improve
+// streaming throughput at the cost of memory. If size <= 0 uses
+// DefaultRecvBufferSize.
+func WithRecvBufferSize(size int) ServerOption {
+	return func(s *Server) {
+		s.recvBufferSize = size
+	}
+}
+
+// WithRecvLimit limits the number of messages and total message bytes the
+// server receives from the client per rpc. Zero or less is unlimited.
+//
+// Guards handlers which buffer client-streamed messages. If exceeded, the
+// server stops reading from the client and MsgRecv returns
+// ErrRecvLimitExceeded. Disabled by default.
+func WithRecvLimit(maxMsgs int, maxBytes int64) ServerOption {
+	return func(s *Server) {
+		s.recvLimitMsgs, s.recvLimitBytes = maxMsgs, maxBytes
+	}
+}
+
+// WithContextFactory sets a function to derive the context of each rpc from
+// the stream context, for example to attach request-scoped values.
+//
+// Called once per rpc before the handler is invoked. The ConnInfo, if any, is
+// attached to the context before calling the factory.
+func WithContextFactory(factory func(ctx context.Context) context.Context) ServerOption {
+	return func(s *Server) {
+		s.ctxFactory = factory
+	}
 }
 
 // NewServer constructs a new SRPC server.
-func NewServer(mux Mux) *Server {
-	return &Server{
+func NewServer(mux Mux, opts ...ServerOption) *Server {
+	s := &Server{
 		mux: mux,
 	}
+	for _, opt := range opts {
+		opt(s)
+	}
+	return s
 }
 
 // GetMux returns the mux.
@@ -26,14 +146,95 @@ func (s *Server) GetMux() Mux {
 }
 
 // HandleStream handles an incoming ReadWriteCloser stream.
+//
+// Canceling ctx cancels the rpc Context: the handler can observe it with
+// Stream.Context() and any pending stream calls return context.Canceled.
+//
+// Returns after the handler returned and the writer was closed. Returns
+// context.Canceled only if ctx was canceled. Otherwise returns nil if the rpc
+// completed, or the error sent by the client or from reading the stream.
 func (s *Server) HandleStream(ctx context.Context, rwc io.ReadWriteCloser) error {
-	subCtx, subCtxCancel := context.WithCancel(ctx)
-	defer subCtxCancel()
-	serverRPC := NewServerRPC(subCtx, s.mux)
+	_, err := s.handleStream(ctx, rwc)
+	return err
+}
+
+// HandleStreamWithReport handles an incoming ReadWriteCloser stream.
+//
+// Returns a report of all errors that occurred while the rpc shut down.
+func (s *Server) HandleStreamWithReport(ctx context.Context, rwc io.ReadWriteCloser) *ShutdownReport {
+	serverRPC, _ := s.handleStream(ctx, rwc)
+	return serverRPC.ShutdownReport()
+}
+
+// HandleStreamWithInfo handles an incoming ReadWriteCloser stream.
+//
+// Returns the service and method invoked by the remote, empty if the stream
+// ended before CallStart was received, and the result of HandleStream.
+func (s *Server) HandleStreamWithInfo(ctx context.Context, rwc io.ReadWriteCloser) (service, method string, err error) {
+	serverRPC, err := s.handleStream(ctx, rwc)
+	return serverRPC.Service(), serverRPC.Method(), err
+}
+
+// HandlePacketTransport handles an incoming rpc over a PacketTransport.
+//
+// Behaves like HandleStream. The framing options of the Server do not apply.
+func (s *Server) HandlePacketTransport(ctx context.Context, t PacketTransport) error {
+	readPump := func(cb PacketHandler, closed CloseHandler) {
+		ReadPacketTransport(t, cb, closed)
+	}
+	_, err := s.handlePackets(ctx, ctx, t, readPump)
+	return err
+}
+
+// handleStream handles an incoming ReadWriteCloser stream.
+// Returns the ServerRPC and the result of Wait.
+func (s *Server) handleStream(ctx context.Context, rwc io.ReadWriteCloser) (*ServerRPC, error) {
+	rpcCtx := ctx
+	if nc, ok := rwc.(net.Conn); ok {
+		if _, ok := GetConnInfo(rpcCtx); !ok {
+			rpcCtx = WithConnInfo(rpcCtx, newConnInfo(nc))
+		}
+	}
 	prw := NewPacketReadWriter(rwc)
-	serverRPC.SetWriter(prw)
-	go prw.ReadPump(serverRPC.HandlePacket, serverRPC.HandleStreamClose)
-	return serverRPC.Wait(ctx)
+	prw.SetDecodeErrorPolicy(s.decodeErrPolicy, s.onDecodeErr)
+	prw.SetFlushBatchSize(s.flushBatchSize)
+	prw.SetFlushPolicy(s.flushPolicy, s.flushDelay)
+	return s.handlePackets(ctx, rpcCtx, prw, prw.ReadPump)
+}
+
+// handlePackets handles an incoming rpc with a packet writer and read pump.
+// rpcCtx is the base context for the rpc.
+// Returns the ServerRPC and the result of Wait after the teardown completed.
+func (s *Server) handlePackets(
+	ctx, rpcCtx context.Context,
+	w Writer,
+	readPump func(cb PacketHandler, closed CloseHandler),
+) (*ServerRPC, error) {
+	if s.ctxFactory != nil {
+		rpcCtx = s.ctxFactory(rpcCtx)
+	}
+	subCtx, subCtxCancel := context.WithCancel(rpcCtx)
+	defer subCtxCancel()
+	var invoker Invoker = s.mux
+	if s.invoker != nil {
+		invoker = s.invoker
+	}
+	serverRPC := NewServerRPC(subCtx, invoker)
+	serverRPC.SetRecvBufferSize(s.recvBufferSize)
+	serverRPC.SetRecvLimit(s.recvLimitMsgs, s.recvLimitBytes)
+	serverRPC.SetDataAfterCompletePolicy(s.dataAfterCompletePolicy)
+	writer := w
+	handlePacket, handleClose := serverRPC.HandlePacket, serverRPC.HandleStreamClose
+	if s.observer != nil {
+		strm := newObservedStream(s.observer, w)
+		writer = strm
+		handlePacket, handleClose = strm.wrapPacketHandler(handlePacket), strm.wrapCloseHandler(handleClose)
+	}
+	serverRPC.SetWriter(writer)
+	go readPump(handlePacket, handleClose)
+	err := serverRPC.Wait(ctx)
+	serverRPC.WaitTeardown()
+	return serverRPC, err
 }
 
 // AcceptMuxedConn runs a loop which calls Accept on a muxer to handle streams.
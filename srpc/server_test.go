@@ -0,0 +1,140 @@
+package srpc
+
+import (
+	"context"
+	"errors"
+	"net"
+	"testing"
+)
+
+// newCtxHandler constructs a testHandler which reports the stream Context.
+func newCtxHandler(ctxCh chan<- context.Context) *testHandler {
+	return newTestHandler(func(strm Stream) error {
+		ctxCh <- strm.Context()
+		return strm.MsgSend(&RawMessage{})
+	})
+}
+
+// testCtxKey is a context key used in tests.
+type testCtxKey struct{}
+
+func TestServer_ContextFactory(t *testing.T) {
+	ctxCh := make(chan context.Context, 1)
+	handler := newCtxHandler(ctxCh)
+	mux := NewMux()
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := NewServer(mux, WithContextFactory(func(ctx context.Context) context.Context {
+		return context.WithValue(ctx, testCtxKey{}, "hello")
+	}))
+	client := NewClient(NewServerPipe(server))
+
+	ctx := context.Background()
+	if err := client.Invoke(ctx, "test.Service", "Method", &RawMessage{}, &RawMessage{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	if val := (<-ctxCh).Value(testCtxKey{}); val != "hello" {
+		t.Fatalf("expected context value %q got %v", "hello", val)
+	}
+}
+
+func TestServer_ConnInfo(t *testing.T) {
+	ctxCh := make(chan context.Context, 1)
+	handler := newCtxHandler(ctxCh)
+	mux := NewMux()
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := NewServer(mux)
+
+	ctx := context.Background()
+	srvPipe, clientPipe := net.Pipe()
+	go func() {
+		_ = server.HandleStream(ctx, srvPipe)
+	}()
+	client := NewClient(NewSingleStreamOpener(clientPipe).GetOpenStreamFunc())
+	if err := client.Invoke(ctx, "test.Service", "Method", &RawMessage{}, &RawMessage{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	info, ok := GetConnInfo(<-ctxCh)
+	if !ok {
+		t.Fatal("expected conn info in the rpc context")
+	}
+	if info.RemoteAddr != srvPipe.RemoteAddr() || info.LocalAddr != srvPipe.LocalAddr() {
+		t.Fatalf("unexpected conn info: %v", info)
+	}
+}
+
+func TestServer_HandleStreamCancel(t *testing.T) {
+	// the handler waits for the stream Context to be canceled.
+	started, canceled := make(chan struct{}), make(chan struct{})
+	handler := newTestHandler(func(strm Stream) error {
+		close(started)
+		<-strm.Context().Done()
+		close(canceled)
+		return context.Canceled
+	})
+	mux := NewMux()
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	server := NewServer(mux)
+
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+	srvPipe, clientPipe := net.Pipe()
+	errCh := make(chan error, 1)
+	go func() {
+		errCh <- server.HandleStream(ctx, srvPipe)
+	}()
+	client := NewClient(NewSingleStreamOpener(clientPipe).GetOpenStreamFunc())
+	strm, err := client.NewStream(context.Background(), "test.Service", "Method", nil)
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	defer strm.Close()
+
+	<-started
+	ctxCancel()
+	if err := <-errCh; err != context.Canceled {
+		t.Fatalf("expected %v got %v", context.Canceled, err)
+	}
+	// the handler returned before HandleStream.
+	select {
+	case <-canceled:
+	default:
+		t.Fatal("expected the handler to return before HandleStream")
+	}
+}
+
+func TestServer_WithInvoker(t *testing.T) {
+	tenantMux := NewMux()
+	if err := tenantMux.Register(newErrHandler(errors.New("tenant"))); err != nil {
+		t.Fatal(err.Error())
+	}
+	router := InvokerRouter(func(ctx context.Context, serviceID, methodID string) Invoker {
+		if ctx.Value(testCtxKey{}) == "tenant" {
+			return tenantMux
+		}
+		return nil
+	})
+	server := NewServer(
+		NewMux(),
+		WithInvoker(router),
+		WithContextFactory(func(ctx context.Context) context.Context {
+			return context.WithValue(ctx, testCtxKey{}, "tenant")
+		}),
+	)
+
+	ctx := context.Background()
+	srvPipe, clientPipe := net.Pipe()
+	go func() {
+		_ = server.HandleStream(ctx, srvPipe)
+	}()
+	client := NewClient(NewSingleStreamOpener(clientPipe).GetOpenStreamFunc())
+	err := client.Invoke(ctx, "test.Service", "Method", &RawMessage{}, &RawMessage{})
+	if err == nil || err.Error() != "tenant" {
+		t.Fatalf("expected the tenant handler got %v", err)
+	}
+}
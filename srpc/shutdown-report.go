@@ -0,0 +1,41 @@
+package srpc
+
+import (
+	"context"
+	"io"
+)
+
+// ShutdownReport contains the errors that occurred while a rpc shut down.
+type ShutdownReport struct {
+	// Err is the primary error the rpc terminated with, if any.
+	Err error
+	// Secondary contains any additional errors that occurred during teardown.
+	Secondary []error
+}
+
+// newShutdownReport builds a report from a list of errors.
+//
+// The first error is the primary, the rest are secondary. nil, io.EOF, and
+// context.Canceled are skipped as they indicate a normal close.
+func newShutdownReport(errs ...error) *ShutdownReport {
+	report := &ShutdownReport{}
+	for _, err := range errs {
+		if err == nil || err == io.EOF || err == context.Canceled {
+			continue
+		}
+		if report.Err == nil {
+			report.Err = err
+		} else {
+			report.Secondary = append(report.Secondary, err)
+		}
+	}
+	return report
+}
+
+// Errors returns all errors in the report with the primary error first.
+func (r *ShutdownReport) Errors() []error {
+	if r.Err == nil {
+		return nil
+	}
+	return append([]error{r.Err}, r.Secondary...)
+}
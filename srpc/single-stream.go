@@ -0,0 +1,62 @@
+package srpc
+
+import (
+	"context"
+	"io"
+	"sync"
+)
+
+// SingleStreamOpener opens a RPC stream over a non-multiplexed transport.
+//
+// The transport carries a single RPC: opening another stream returns
+// ErrStreamBusy. Use a stream multiplexer (see NewClientWithConn) to run
+// concurrent RPCs over one connection.
+type SingleStreamOpener struct {
+	// mtx guards below fields
+	mtx sync.Mutex
+	// rwc is the transport
+	rwc io.ReadWriteCloser
+	// opened indicates the stream was opened
+	opened bool
+}
+
+// NewSingleStreamOpener constructs a new SingleStreamOpener with a transport.
+func NewSingleStreamOpener(rwc io.ReadWriteCloser) *SingleStreamOpener {
+	return &SingleStreamOpener{rwc: rwc}
+}
+
+// GetOpenStreamFunc returns the OpenStream func.
+func (o *SingleStreamOpener) GetOpenStreamFunc() OpenStreamFunc {
+	return o.OpenStream
+}
+
+// TakeTransport returns the transport if no stream was opened.
+//
+// Use to reclaim the transport for another protocol. Further calls to
+// OpenStream return ErrStreamBusy. Returns nil, false if a stream was opened
+// or the transport was already taken.
+func (o *SingleStreamOpener) TakeTransport() (io.ReadWriteCloser, bool) {
+	o.mtx.Lock()
+	defer o.mtx.Unlock()
+	if o.opened {
+		return nil, false
+	}
+	o.opened = true
+	return o.rwc, true
+}
+
+// OpenStream opens the stream with the remote.
+// Returns ErrStreamBusy if the stream was already opened.
+func (o *SingleStreamOpener) OpenStream(ctx context.Context, msgHandler PacketHandler, closeHandler CloseHandler) (Writer, error) {
+	o.mtx.Lock()
+	opened := o.opened
+	o.opened = true
+	o.mtx.Unlock()
+	if opened {
+		return nil, ErrStreamBusy
+	}
+
+	rw := NewPacketReadWriter(o.rwc)
+	go rw.ReadPump(msgHandler, closeHandler)
+	return rw, nil
+}
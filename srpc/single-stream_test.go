@@ -0,0 +1,21 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+)
+
+func TestSingleStreamOpener_TakeTransport(t *testing.T) {
+	rwc := &bufferRWC{}
+	opener := NewSingleStreamOpener(rwc)
+	taken, ok := opener.TakeTransport()
+	if !ok || taken != rwc {
+		t.Fatal("expected to take the transport")
+	}
+	if _, ok := opener.TakeTransport(); ok {
+		t.Fatal("expected the transport to be taken once")
+	}
+	if _, err := opener.OpenStream(context.Background(), nil, nil); err != ErrStreamBusy {
+		t.Fatalf("expected %v got %v", ErrStreamBusy, err)
+	}
+}
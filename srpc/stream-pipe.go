@@ -16,14 +16,17 @@ type pipeStream struct {
 	// closeOnce ensures we close only once.
 	closeOnce sync.Once
 	// dataCh is the data channel
+	// never closed: the remote may be sending concurrently.
 	dataCh chan []byte
+	// recvClosed is closed when the remote closed its send side
+	recvClosed chan struct{}
 }
 
 // NewPipeStream constructs a new in-memory stream.
 func NewPipeStream(ctx context.Context) (Stream, Stream) {
-	s1 := &pipeStream{dataCh: make(chan []byte, 5)}
+	s1 := &pipeStream{dataCh: make(chan []byte, 5), recvClosed: make(chan struct{})}
 	s1.ctx, s1.ctxCancel = context.WithCancel(ctx)
-	s2 := &pipeStream{other: s1, dataCh: make(chan []byte, 5)}
+	s2 := &pipeStream{other: s1, dataCh: make(chan []byte, 5), recvClosed: make(chan struct{})}
 	s2.ctx, s2.ctxCancel = context.WithCancel(ctx)
 	s1.other = s2
 	return s1, s2
@@ -35,14 +38,24 @@ func (p *pipeStream) Context() context.Context {
 }
 
 // MsgSend sends the message to the remote.
+//
+// Returns ErrCompleted if the send side is closed, or context.Canceled if
+// either end of the stream was closed.
 func (p *pipeStream) MsgSend(msg Message) error {
 	data, err := msg.MarshalVT()
 	if err != nil {
 		return err
 	}
+	if p.IsSendClosed() {
+		return ErrCompleted
+	}
 	select {
 	case <-p.ctx.Done():
 		return context.Canceled
+	case <-p.other.ctx.Done():
+		return context.Canceled
+	case <-p.other.recvClosed:
+		return ErrCompleted
 	case p.other.dataCh <- data:
 		return nil
 	}
@@ -51,23 +64,72 @@ func (p *pipeStream) MsgSend(msg Message) error {
 // MsgRecv receives an incoming message from the remote.
 // Parses the message into the object at msg.
 func (p *pipeStream) MsgRecv(msg Message) error {
+	return p.MsgRecvContext(p.ctx, msg)
+}
+
+// MsgRecvContext receives an incoming message from the remote.
+// ctx must be derived from the stream Context.
+func (p *pipeStream) MsgRecvContext(ctx context.Context, msg Message) error {
 	select {
-	case <-p.ctx.Done():
+	case <-ctx.Done():
 		return context.Canceled
-	case data, ok := <-p.dataCh:
-		if !ok {
+	case data := <-p.dataCh:
+		return msg.UnmarshalVT(data)
+	case <-p.recvClosed:
+		// read any messages sent before the remote closed its send side.
+		select {
+		case data := <-p.dataCh:
+			return msg.UnmarshalVT(data)
+		default:
 			return io.EOF
 		}
-		return msg.UnmarshalVT(data)
 	}
 }
 
+// RecvClosed returns a channel closed when the remote closed its send side.
+func (p *pipeStream) RecvClosed() <-chan struct{} {
+	return p.recvClosed
+}
+
+// IsRecvClosed checks if the remote has closed its send side.
+func (p *pipeStream) IsRecvClosed() bool {
+	select {
+	case <-p.recvClosed:
+		return true
+	default:
+		return false
+	}
+}
+
+// IsSendClosed checks if CloseSend or Close was called.
+func (p *pipeStream) IsSendClosed() bool {
+	select {
+	case <-p.other.recvClosed:
+		return true
+	default:
+		return false
+	}
+}
+
+// Flush writes any buffered messages to the remote.
+// Messages are not buffered: does nothing.
+func (p *pipeStream) Flush() error {
+	return nil
+}
+
 // CloseSend signals to the remote that we will no longer send any messages.
 func (p *pipeStream) CloseSend() error {
 	p.closeRemote()
 	return nil
 }
 
+// Cancel signals to the remote that we will no longer send any messages.
+// The pipe does not carry errors: the remote sees io.EOF as with CloseSend.
+func (p *pipeStream) Cancel() error {
+	p.closeRemote()
+	return nil
+}
+
 // Close closes the stream.
 func (p *pipeStream) Close() error {
 	p.ctxCancel()
@@ -75,10 +137,10 @@ func (p *pipeStream) Close() error {
 	return nil
 }
 
-// closeRemote closes the remote data channel.
+// closeRemote signals to the remote that we will no longer send any messages.
 func (p *pipeStream) closeRemote() {
 	p.closeOnce.Do(func() {
-		close(p.other.dataCh)
+		close(p.other.recvClosed)
 	})
 }
 
@@ -0,0 +1,67 @@
+package srpc
+
+import (
+	"io"
+)
+
+// StreamRwc implements io.ReadWriteCloser over a Stream.
+//
+// Each Write sends the data as a single RawMessage. Read receives messages and
+// buffers any data which does not fit in the read buffer. Use to tunnel byte
+// oriented protocols over a bidi rpc.
+type StreamRwc struct {
+	// strm is the underlying stream
+	strm Stream
+	// buf contains the unread remainder of the last message
+	buf []byte
+}
+
+// NewStreamRwc constructs a new io.ReadWriteCloser over a Stream.
+func NewStreamRwc(strm Stream) *StreamRwc {
+	return &StreamRwc{strm: strm}
+}
+
+// Read reads data from the remote.
+//
+// Returns io.EOF when the remote closes its send side.
+func (s *StreamRwc) Read(p []byte) (int, error) {
+	for len(s.buf) == 0 {
+		if len(p) == 0 {
+			return 0, nil
+		}
+		var msg RawMessage
+		if err := s.strm.MsgRecv(&msg); err != nil {
+			return 0, err
+		}
+		s.buf = msg
+	}
+	n := copy(p, s.buf)
+	s.buf = s.buf[n:]
+	return n, nil
+}
+
+// Write writes data to the remote as a single message.
+func (s *StreamRwc) Write(p []byte) (int, error) {
+	if len(p) == 0 {
+		return 0, nil
+	}
+	// copy: some streams retain the message after MsgSend returns.
+	msg := RawMessage(append([]byte(nil), p...))
+	if err := s.strm.MsgSend(&msg); err != nil {
+		return 0, err
+	}
+	return len(p), nil
+}
+
+// CloseWrite closes the send side of the stream.
+func (s *StreamRwc) CloseWrite() error {
+	return s.strm.CloseSend()
+}
+
+// Close closes the stream.
+func (s *StreamRwc) Close() error {
+	return s.strm.Close()
+}
+
+// _ is a type assertion
+var _ io.ReadWriteCloser = ((*StreamRwc)(nil))
@@ -0,0 +1,60 @@
+package srpc
+
+import (
+	"bytes"
+	"context"
+	"io"
+	"testing"
+)
+
+func TestStreamRwc(t *testing.T) {
+	ctx, ctxCancel := context.WithCancel(context.Background())
+	defer ctxCancel()
+
+	s1, s2 := NewPipeStream(ctx)
+	rwc1, rwc2 := NewStreamRwc(s1), NewStreamRwc(s2)
+
+	// echo everything received on the remote end.
+	go func() {
+		_, _ = io.Copy(rwc2, rwc2)
+		_ = rwc2.CloseWrite()
+	}()
+
+	data := bytes.Repeat([]byte("hello world "), 100)
+	errCh := make(chan error, 1)
+	go func() {
+		// write in uneven chunks reusing the same buffer.
+		buf := make([]byte, 7)
+		for i := 0; i < len(data); i += len(buf) {
+			n := copy(buf, data[i:])
+			if _, err := rwc1.Write(buf[:n]); err != nil {
+				errCh <- err
+				return
+			}
+		}
+		errCh <- rwc1.CloseWrite()
+	}()
+
+	// read with a buffer smaller than the messages.
+	var out bytes.Buffer
+	buf := make([]byte, 3)
+	for {
+		n, err := rwc1.Read(buf)
+		out.Write(buf[:n])
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	if err := <-errCh; err != nil {
+		t.Fatal(err.Error())
+	}
+	if !bytes.Equal(out.Bytes(), data) {
+		t.Fatalf("expected %d bytes got %d", len(data), out.Len())
+	}
+	if err := rwc1.Close(); err != nil {
+		t.Fatal(err.Error())
+	}
+}
@@ -17,8 +17,156 @@ type Stream interface {
 	MsgRecv(msg Message) error
 
 	// CloseSend signals to the remote that we will no longer send any messages.
+	// Does nothing if the send side is already closed.
 	CloseSend() error
 
 	// Close closes the stream.
 	Close() error
 }
+
+// streamUnwrapper is a Stream which wraps another Stream.
+type streamUnwrapper interface {
+	// Unwrap returns the underlying Stream.
+	Unwrap() Stream
+}
+
+// streamAs finds the first Stream implementing T, unwrapping wrapped Streams.
+func streamAs[T any](strm Stream) (T, bool) {
+	for strm != nil {
+		if t, ok := strm.(T); ok {
+			return t, true
+		}
+		u, ok := strm.(streamUnwrapper)
+		if !ok {
+			break
+		}
+		strm = u.Unwrap()
+	}
+	var empty T
+	return empty, false
+}
+
+// recvClosedStream is a Stream which signals when the remote closed its send side.
+type recvClosedStream interface {
+	// RecvClosed returns a channel which is closed when the remote has closed
+	// its send side. Messages sent before that can still be read w/ MsgRecv,
+	// which returns io.EOF on a clean close or the error otherwise.
+	RecvClosed() <-chan struct{}
+	// IsRecvClosed checks if the remote has closed its send side.
+	IsRecvClosed() bool
+}
+
+// StreamRecvClosed returns a channel which is closed when the remote has
+// closed its send side.
+//
+// Returns nil if the stream does not support it, which blocks forever.
+func StreamRecvClosed(strm Stream) <-chan struct{} {
+	if s, ok := streamAs[recvClosedStream](strm); ok {
+		return s.RecvClosed()
+	}
+	return nil
+}
+
+// IsStreamRecvClosed checks if the remote has closed its send side.
+// Returns false if the stream does not support it.
+func IsStreamRecvClosed(strm Stream) bool {
+	if s, ok := streamAs[recvClosedStream](strm); ok {
+		return s.IsRecvClosed()
+	}
+	return false
+}
+
+// sendClosedStream is a Stream which reports if its send side is closed.
+type sendClosedStream interface {
+	// IsSendClosed checks if CloseSend or Close was called.
+	IsSendClosed() bool
+}
+
+// IsStreamSendClosed checks if CloseSend or Close was called on the stream.
+// Returns false if the stream does not support it.
+func IsStreamSendClosed(strm Stream) bool {
+	if s, ok := streamAs[sendClosedStream](strm); ok {
+		return s.IsSendClosed()
+	}
+	return false
+}
+
+// flushStream is a Stream which buffers outgoing messages.
+type flushStream interface {
+	// Flush writes any buffered messages to the remote.
+	// Messages are buffered if the transport uses FlushBuffered.
+	Flush() error
+}
+
+// FlushStream writes any buffered messages on the stream to the remote.
+// Does nothing if the stream does not buffer messages.
+func FlushStream(strm Stream) error {
+	if s, ok := streamAs[flushStream](strm); ok {
+		return s.Flush()
+	}
+	return nil
+}
+
+// cancelStream is a Stream which can cancel the rpc without releasing it.
+type cancelStream interface {
+	// Cancel signals to the remote to cancel the rpc and closes the send side.
+	//
+	// Unlike Close, does not release the stream: messages sent by the remote
+	// before it handled the cancel can still be read with MsgRecv.
+	// Does nothing if the send side is already closed.
+	Cancel() error
+}
+
+// CancelStream signals to the remote to cancel the rpc.
+// Calls Close if the stream does not support canceling.
+func CancelStream(strm Stream) error {
+	if s, ok := streamAs[cancelStream](strm); ok {
+		return s.Cancel()
+	}
+	return strm.Close()
+}
+
+// msgSendCompleter is a Stream which can send a message and close the send
+// side in one packet.
+type msgSendCompleter interface {
+	// MsgSendComplete sends the message and closes the send side.
+	MsgSendComplete(msg Message) error
+}
+
+// msgRecvContexter is a Stream which can receive a message with a Context.
+//
+// Wrappers which override MsgRecv must also implement it, otherwise
+// MsgRecvContext bypasses the wrapper through Unwrap.
+type msgRecvContexter interface {
+	// MsgRecvContext receives a message, returning early if ctx is canceled.
+	MsgRecvContext(ctx context.Context, msg Message) error
+}
+
+// MsgRecvContext receives an incoming message from the stream.
+//
+// ctx must be derived from the stream Context. If the stream or a stream it
+// wraps supports it, returns context.Canceled as soon as ctx is canceled,
+// otherwise calls MsgRecv which returns when the stream Context is canceled.
+func MsgRecvContext(ctx context.Context, strm Stream, msg Message) error {
+	if c, ok := streamAs[msgRecvContexter](strm); ok {
+		return c.MsgRecvContext(ctx, msg)
+	}
+	if ctx.Err() != nil {
+		return context.Canceled
+	}
+	return strm.MsgRecv(msg)
+}
+
+// MsgSendComplete sends the message and closes the send side of the stream.
+//
+// Writes a single packet if the stream supports it, otherwise calls MsgSend
+// and CloseSend. Useful to send the last message of a rpc.
+func MsgSendComplete(strm Stream, msg Message) error {
+	if c, ok := strm.(msgSendCompleter); ok {
+		return c.MsgSendComplete(msg)
+	}
+	if err := strm.MsgSend(msg); err != nil {
+		return err
+	}
+	return strm.CloseSend()
+}
@@ -0,0 +1,115 @@
+package srpc
+
+import (
+	"context"
+	"sync"
+	"time"
+)
+
+// TimeoutMux wraps a Mux to apply per-method handler timeouts.
+//
+// When the timeout elapses the handler Context is canceled, any pending MsgRecv
+// returns context.Canceled, and the rpc ends with ErrMethodTimeout once the
+// handler returns. Methods without a timeout run without a deadline.
+type TimeoutMux struct {
+	// Mux is the underlying mux
+	Mux
+
+	// mtx guards below fields
+	mtx sync.RWMutex
+	// timeouts contains the timeouts by service and method
+	timeouts map[timeoutMuxKey]time.Duration
+}
+
+// timeoutMuxKey is the key for a method timeout.
+type timeoutMuxKey struct {
+	serviceID, methodID string
+}
+
+// NewTimeoutMux constructs a new TimeoutMux wrapping a Mux.
+func NewTimeoutMux(mux Mux) *TimeoutMux {
+	return &TimeoutMux{Mux: mux, timeouts: make(map[timeoutMuxKey]time.Duration)}
+}
+
+// SetMethodTimeout sets the timeout for a method.
+// If timeout <= 0, clears the timeout.
+func (m *TimeoutMux) SetMethodTimeout(serviceID, methodID string, timeout time.Duration) {
+	key := timeoutMuxKey{serviceID: serviceID, methodID: methodID}
+	m.mtx.Lock()
+	if timeout > 0 {
+		m.timeouts[key] = timeout
+	} else {
+		delete(m.timeouts, key)
+	}
+	m.mtx.Unlock()
+}
+
+// HasServiceMethod checks if the <service, method> pair has a handler.
+// Returns true if the underlying Mux is not a QueryableInvoker.
+func (m *TimeoutMux) HasServiceMethod(serviceID, methodID string) bool {
+	return invokerHasServiceMethod(m.Mux, serviceID, methodID)
+}
+
+// InvokeMethod invokes the method matching the service & method ID.
+// Returns false, nil if not found.
+// If service string is empty, ignore it.
+func (m *TimeoutMux) InvokeMethod(serviceID, methodID string, strm Stream) (bool, error) {
+	m.mtx.RLock()
+	timeout := m.timeouts[timeoutMuxKey{serviceID: serviceID, methodID: methodID}]
+	m.mtx.RUnlock()
+	if timeout <= 0 {
+		return m.Mux.InvokeMethod(serviceID, methodID, strm)
+	}
+
+	ctx, ctxCancel := context.WithTimeout(strm.Context(), timeout)
+	defer ctxCancel()
+	ok, err := m.Mux.InvokeMethod(serviceID, methodID, &timeoutStream{Stream: strm, ctx: ctx})
+	if ok && ctx.Err() == context.DeadlineExceeded {
+		return true, ErrMethodTimeout
+	}
+	return ok, err
+}
+
+// timeoutStream overrides the Context of a Stream.
+type timeoutStream struct {
+	Stream
+	// ctx is the context with the timeout
+	ctx context.Context
+}
+
+// Context is canceled when the Stream is no longer valid.
+func (s *timeoutStream) Context() context.Context {
+	return s.ctx
+}
+
+// MsgSend sends the message to the remote.
+func (s *timeoutStream) MsgSend(msg Message) error {
+	if s.ctx.Err() != nil {
+		return context.Canceled
+	}
+	return s.Stream.MsgSend(msg)
+}
+
+// MsgRecv receives an incoming message from the remote.
+// Returns context.Canceled when the timeout elapses.
+func (s *timeoutStream) MsgRecv(msg Message) error {
+	return MsgRecvContext(s.ctx, s.Stream, msg)
+}
+
+// MsgRecvContext receives an incoming message from the remote.
+// ctx must be derived from the stream Context.
+func (s *timeoutStream) MsgRecvContext(ctx context.Context, msg Message) error {
+	return MsgRecvContext(ctx, s.Stream, msg)
+}
+
+// Unwrap returns the underlying Stream.
+func (s *timeoutStream) Unwrap() Stream {
+	return s.Stream
+}
+
+// _ is a type assertion
+var (
+	_ Mux              = ((*TimeoutMux)(nil))
+	_ QueryableInvoker = ((*TimeoutMux)(nil))
+	_ Stream           = ((*timeoutStream)(nil))
+)
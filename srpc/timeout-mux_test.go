@@ -0,0 +1,38 @@
+package srpc
+
+import (
+	"context"
+	"testing"
+	"time"
+)
+
+// newBlockHandler constructs a testHandler which closes started, then blocks
+// in MsgRecv. Ignores the recv error.
+func newBlockHandler(started chan struct{}) *testHandler {
+	return newTestHandler(func(strm Stream) error {
+		close(started)
+		var msg RawMessage
+		_ = strm.MsgRecv(&msg)
+		return nil
+	})
+}
+
+func TestTimeoutMux_WakesRecv(t *testing.T) {
+	handler := newBlockHandler(make(chan struct{}))
+	mux := NewMux()
+	if err := mux.Register(handler); err != nil {
+		t.Fatal(err.Error())
+	}
+	tmux := NewTimeoutMux(mux)
+	tmux.SetMethodTimeout("test.Service", "Method", 50*time.Millisecond)
+
+	// the stream is never canceled: only the timeout wakes MsgRecv.
+	strm, _ := NewPipeStream(context.Background())
+	ok, err := tmux.InvokeMethod("test.Service", "Method", strm)
+	if !ok || err != ErrMethodTimeout {
+		t.Fatalf("expected %v got %v, %v", ErrMethodTimeout, ok, err)
+	}
+	if strm.Context().Err() != nil {
+		t.Fatal("expected the stream to remain open")
+	}
+}
@@ -0,0 +1,42 @@
+package srpc
+
+import (
+	"context"
+	"runtime/trace"
+)
+
+// Task types and log keys emitted to runtime/trace.
+//
+// Tasks are only recorded while a trace is active (see runtime/trace.Start or
+// go test -trace). The names are stable and can be used to filter traces.
+const (
+	// TraceTaskClientCall is the task type for a client-side rpc.
+	TraceTaskClientCall = "srpc.ClientCall"
+	// TraceTaskServerCall is the task type for a server-side rpc.
+	TraceTaskServerCall = "srpc.ServerCall"
+	// TraceKeyService is the log key for the rpc service id.
+	TraceKeyService = "service"
+	// TraceKeyMethod is the log key for the rpc method id.
+	TraceKeyMethod = "method"
+	// TraceKeyError is the log key for the error the rpc ended with, if any.
+	TraceKeyError = "error"
+)
+
+// startTraceTask starts a runtime/trace task for a rpc.
+//
+// Returns the task context and a function to end the task with the outcome.
+// If tracing is not enabled, returns ctx and a no-op function.
+func startTraceTask(ctx context.Context, taskType, service, method string) (context.Context, func(err error)) {
+	if !trace.IsEnabled() {
+		return ctx, func(error) {}
+	}
+	ctx, task := trace.NewTask(ctx, taskType)
+	trace.Log(ctx, TraceKeyService, service)
+	trace.Log(ctx, TraceKeyMethod, method)
+	return ctx, func(err error) {
+		if err != nil {
+			trace.Log(ctx, TraceKeyError, err.Error())
+		}
+		task.End()
+	}
+}
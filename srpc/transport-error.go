@@ -0,0 +1,32 @@
+package srpc
+
+import "errors"
+
+// TransportError is returned if the transport failed before the rpc completed.
+//
+// Distinguishes a broken transport from an error returned by the remote. If
+// the transport was closed without an error before the rpc completed, Err is
+// io.ErrUnexpectedEOF.
+type TransportError struct {
+	// Err is the underlying error.
+	Err error
+}
+
+// Error returns the error string.
+func (e *TransportError) Error() string {
+	return "transport error: " + e.Err.Error()
+}
+
+// Unwrap returns the underlying error.
+func (e *TransportError) Unwrap() error {
+	return e.Err
+}
+
+// IsTransportError checks if the error is or wraps a TransportError.
+func IsTransportError(err error) bool {
+	var terr *TransportError
+	return errors.As(err, &terr)
+}
+
+// _ is a type assertion
+var _ error = ((*TransportError)(nil))
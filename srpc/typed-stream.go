@@ -0,0 +1,71 @@
+package srpc
+
+import "context"
+
+// ClientStream is a client-streaming rpc with typed messages.
+type ClientStream[I, O Message] struct {
+	// Stream is the underlying stream
+	Stream
+}
+
+// NewClientStream starts a client-streaming rpc with the remote.
+//
+// Send the requests with Send, then call CloseAndRecv to read the response.
+func NewClientStream[I, O Message](ctx context.Context, c Client, service, method string) (*ClientStream[I, O], error) {
+	strm, err := c.NewStream(ctx, service, method, nil)
+	if err != nil {
+		return nil, err
+	}
+	return &ClientStream[I, O]{Stream: strm}, nil
+}
+
+// Send sends a request to the remote.
+func (s *ClientStream[I, O]) Send(msg I) error {
+	return s.MsgSend(msg)
+}
+
+// CloseAndRecv closes the send side and reads the response into out.
+func (s *ClientStream[I, O]) CloseAndRecv(out O) error {
+	if err := s.CloseSend(); err != nil {
+		return err
+	}
+	return s.MsgRecv(out)
+}
+
+// Unwrap returns the underlying Stream.
+func (s *ClientStream[I, O]) Unwrap() Stream {
+	return s.Stream
+}
+
+// BidiStream is a bidirectional streaming rpc with typed messages.
+type BidiStream[I, O Message] struct {
+	// Stream is the underlying stream
+	Stream
+}
+
+// NewBidiStream starts a bidirectional streaming rpc with the remote.
+//
+// Send and Recv can be called concurrently. Call CloseSend when done sending.
+func NewBidiStream[I, O Message](ctx context.Context, c Client, service, method string) (*BidiStream[I, O], error) {
+	strm, err := c.NewStream(ctx, service, method, nil)
+	if err != nil {
+		return nil, err
+	}
+	return &BidiStream[I, O]{Stream: strm}, nil
+}
+
+// Send sends a message to the remote.
+func (s *BidiStream[I, O]) Send(msg I) error {
+	return s.MsgSend(msg)
+}
+
+// Recv reads the next message from the remote into out.
+// Returns io.EOF when the remote closed its send side.
+func (s *BidiStream[I, O]) Recv(out O) error {
+	return s.MsgRecv(out)
+}
+
+// Unwrap returns the underlying Stream.
+func (s *BidiStream[I, O]) Unwrap() Stream {
+	return s.Stream
+}
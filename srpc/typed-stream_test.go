@@ -0,0 +1,56 @@
+package srpc
+
+import (
+	"context"
+	"io"
+	"testing"
+)
+
+func TestTypedStreams(t *testing.T) {
+	ctx := context.Background()
+	mux := NewMux()
+	if err := mux.Register(interopHandler{}); err != nil {
+		t.Fatal(err.Error())
+	}
+	client := NewClient(NewServerPipe(NewServer(mux)))
+
+	cstrm, err := NewClientStream[*RawMessage, *RawMessage](ctx, client, interopService, "ClientStream")
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	for _, body := range []string{"a", "b"} {
+		msg := RawMessage(body)
+		if err := cstrm.Send(&msg); err != nil {
+			t.Fatal(err.Error())
+		}
+	}
+	var count RawMessage
+	if err := cstrm.CloseAndRecv(&count); err != nil {
+		t.Fatal(err.Error())
+	}
+	if len(count) != 1 || count[0] != 2 {
+		t.Fatalf("expected count 2 got %v", count)
+	}
+
+	bstrm, err := NewBidiStream[*RawMessage, *RawMessage](ctx, client, interopService, "BidiStream")
+	if err != nil {
+		t.Fatal(err.Error())
+	}
+	msg := RawMessage("hello")
+	if err := bstrm.Send(&msg); err != nil {
+		t.Fatal(err.Error())
+	}
+	var out RawMessage
+	if err := bstrm.Recv(&out); err != nil {
+		t.Fatal(err.Error())
+	}
+	if string(out) != "hello" {
+		t.Fatalf("expected hello got %q", string(out))
+	}
+	if err := bstrm.CloseSend(); err != nil {
+		t.Fatal(err.Error())
+	}
+	if err := bstrm.Recv(&out); err != io.EOF {
+		t.Fatalf("expected %v got %v", io.EOF, err)
+	}
+}
@@ -7,3 +7,9 @@ type Writer interface {
 	// Close closes the writer.
 	Close() error
 }
+
+// Flusher is a Writer which buffers packets and can be flushed.
+type Flusher interface {
+	// Flush writes any buffered packets to the remote.
+	Flush() error
+}